@@ -0,0 +1,330 @@
+//! Typed syntax tree for parsed Markdown documents.
+//!
+//! The tree is built from the Pest parse output and is independent of the
+//! grammar's internal rule names, so consumers can inspect a document without
+//! matching on [`Rule`](crate::Rule) variants. All text is stored unescaped;
+//! HTML escaping happens only when rendering.
+
+use std::collections::HashMap;
+
+/// Root of a parsed Markdown document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document {
+    /// Metadata from YAML or TOML front matter at the start of the input.
+    pub front_matter: Option<FrontMatter>,
+    /// Top-level blocks in source order.
+    pub blocks: Vec<Block>,
+}
+
+impl Document {
+    /// Collect link reference definitions by normalized label, including
+    /// definitions inside lists and blockquotes. The first definition of a
+    /// label wins.
+    pub fn definitions(&self) -> HashMap<String, &LinkDefinition> {
+        let mut definitions = HashMap::new();
+        walk_blocks(&self.blocks, &mut |block| {
+            if let Block::Definition(definition) = block {
+                definitions
+                    .entry(normalize_label(&definition.label))
+                    .or_insert(definition);
+            }
+        });
+        definitions
+    }
+
+    /// Collect footnote definitions by normalized label, including
+    /// definitions inside lists and blockquotes. The first definition of a
+    /// label wins.
+    pub fn footnote_definitions(&self) -> HashMap<String, &FootnoteDefinition> {
+        let mut definitions = HashMap::new();
+        walk_blocks(&self.blocks, &mut |block| {
+            if let Block::FootnoteDefinition(definition) = block {
+                definitions
+                    .entry(normalize_label(&definition.label))
+                    .or_insert(definition);
+            }
+        });
+        definitions
+    }
+
+    /// Collect all task list items of the document, including items of
+    /// nested lists and lists inside blockquotes.
+    pub fn task_items(&self) -> Vec<&ListItem> {
+        let mut tasks = Vec::new();
+        collect_task_items(&self.blocks, &mut tasks);
+        tasks
+    }
+}
+
+/// Visit all blocks in source order, descending into container blocks.
+pub(crate) fn walk_blocks<'a>(blocks: &'a [Block], visit: &mut impl FnMut(&'a Block)) {
+    for block in blocks {
+        visit(block);
+        match block {
+            Block::List { items, .. } => {
+                for item in items {
+                    walk_blocks(&item.children, visit);
+                }
+            }
+            Block::BlockQuote { children } | Block::Alert { children, .. } => {
+                walk_blocks(children, visit)
+            }
+            Block::FootnoteDefinition(definition) => walk_blocks(&definition.children, visit),
+            _ => {}
+        }
+    }
+}
+
+/// Normalize a link label for matching: surrounding whitespace is removed,
+/// inner whitespace collapsed to one space and the label case folded, so
+/// `[Foo  Bar]` matches `[foo bar]` and `[ẞ]` matches `[ss]`.
+pub fn normalize_label(label: &str) -> String {
+    let collapsed = label.split_whitespace().collect::<Vec<_>>().join(" ");
+    // Lowercase, uppercase, lowercase approximates Unicode full case folding
+    collapsed.to_lowercase().to_uppercase().to_lowercase()
+}
+
+fn collect_task_items<'a>(blocks: &'a [Block], tasks: &mut Vec<&'a ListItem>) {
+    for block in blocks {
+        match block {
+            Block::List { items, .. } => {
+                for item in items {
+                    if item.checked.is_some() {
+                        tasks.push(item);
+                    }
+                    collect_task_items(&item.children, tasks);
+                }
+            }
+            Block::BlockQuote { children } | Block::Alert { children, .. } => {
+                collect_task_items(children, tasks)
+            }
+            Block::FootnoteDefinition(definition) => {
+                collect_task_items(&definition.children, tasks)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Metadata block at the start of a document, such as
+/// `---\ntitle: Home\n---`. It is not rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrontMatter {
+    /// `---` delimits YAML, `+++` TOML.
+    pub format: FrontMatterFormat,
+    /// Text between the delimiter lines.
+    pub raw: String,
+    /// Fields by key. Keys inside TOML tables are prefixed with the table
+    /// name, e.g. `author.name`; nested YAML mappings are skipped.
+    pub fields: HashMap<String, FrontMatterValue>,
+}
+
+/// Syntax of a [`FrontMatter`] block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// YAML between `---` lines.
+    Yaml,
+    /// TOML between `+++` lines.
+    Toml,
+}
+
+/// Value of a [`FrontMatter`] field. Quoted strings are unquoted; numbers,
+/// dates and booleans are kept as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrontMatterValue {
+    /// Single value such as `title: Home` or `date = 2024-05-01`.
+    Text(String),
+    /// List such as `tags: [a, b]`, a YAML block sequence or a TOML array.
+    List(Vec<String>),
+}
+
+/// Block-level element of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// ATX heading such as `## Title`, or a setext heading. `id` is set by
+    /// an explicit `{#id}` attribute; otherwise the renderer derives a slug
+    /// from the heading text.
+    Heading {
+        level: u8,
+        id: Option<String>,
+        children: Vec<Inline>,
+    },
+    /// Paragraph of inline content.
+    Paragraph { children: Vec<Inline> },
+    /// Blockquote holding nested blocks.
+    BlockQuote { children: Vec<Block> },
+    /// Callout written as a GitHub alert (`> [!NOTE]`) or a Docusaurus
+    /// admonition (`:::note`), with an optional custom title.
+    Alert {
+        kind: AlertKind,
+        title: Option<String>,
+        children: Vec<Block>,
+    },
+    /// Ordered or unordered list. Paragraphs of tight lists render
+    /// without `<p>` tags.
+    List {
+        kind: ListKind,
+        tight: bool,
+        items: Vec<ListItem>,
+    },
+    /// Fenced or indented code block with its raw contents. The info string
+    /// holds everything after the opening fence, e.g. `python {linenos=true}`;
+    /// its first word is the language. Indented blocks have an empty info string.
+    CodeBlock { info: String, literal: String },
+    /// Horizontal rule (`---`, `***`, `___`).
+    ThematicBreak,
+    /// Raw HTML block such as `<details>` or `<!-- comment -->`, kept verbatim.
+    Html(String),
+    /// Link reference definition (`[label]: url "title"`); renders nothing.
+    Definition(LinkDefinition),
+    /// Footnote definition (`[^label]: text`); rendered in the footnotes
+    /// section at the end of the document when referenced.
+    FootnoteDefinition(FootnoteDefinition),
+    /// Pipe table with per-column alignment, a header row and body rows.
+    Table {
+        alignments: Vec<Alignment>,
+        header: Vec<TableCell>,
+        rows: Vec<Vec<TableCell>>,
+    },
+}
+
+/// Link reference definition used to resolve reference links and images.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LinkDefinition {
+    /// Label as written, matched after [`normalize_label`].
+    pub label: String,
+    /// Link destination.
+    pub url: String,
+    /// Optional link title.
+    pub title: Option<String>,
+}
+
+/// Footnote definition referenced by [`Inline::FootnoteReference`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FootnoteDefinition {
+    /// Label as written, matched after [`normalize_label`].
+    pub label: String,
+    /// Blocks of the footnote body.
+    pub children: Vec<Block>,
+}
+
+/// Form of a reference link or image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// `[text][label]`
+    Full,
+    /// `[label][]`
+    Collapsed,
+    /// `[label]`
+    Shortcut,
+}
+
+/// Kind of a [`Block::Alert`]. The Docusaurus `info` admonition is a note
+/// and `danger` a caution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    /// `[!NOTE]`, `:::note` or `:::info`
+    Note,
+    /// `[!TIP]` or `:::tip`
+    Tip,
+    /// `[!IMPORTANT]` or `:::important`
+    Important,
+    /// `[!WARNING]` or `:::warning`
+    Warning,
+    /// `[!CAUTION]`, `:::caution` or `:::danger`
+    Caution,
+}
+
+/// Column alignment of a [`Block::Table`], taken from the delimiter row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// No alignment (`---`).
+    #[default]
+    None,
+    /// Left aligned (`:---`).
+    Left,
+    /// Centered (`:---:`).
+    Center,
+    /// Right aligned (`---:`).
+    Right,
+}
+
+/// Single cell of a [`Block::Table`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableCell {
+    /// Inline content of the cell.
+    pub children: Vec<Inline>,
+}
+
+/// Marker style of a [`Block::List`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    /// Unordered list with its bullet character (`-`, `*` or `+`).
+    Bullet(char),
+    /// Ordered list with its start number and delimiter (`.` or `)`).
+    Ordered { start: u64, delimiter: char },
+}
+
+/// Single item of a [`Block::List`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListItem {
+    /// Task state: `Some(true)` for `[x]`, `Some(false)` for `[ ]`,
+    /// `None` for ordinary items.
+    pub checked: Option<bool>,
+    /// Blocks inside the item, including nested lists.
+    pub children: Vec<Block>,
+}
+
+/// Inline element inside a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    /// Literal text.
+    Text(String),
+    /// Inline code span.
+    Code(String),
+    /// Emphasis (`*text*` or `_text_`).
+    Emphasis(Vec<Inline>),
+    /// Strong emphasis (`**text**` or `__text__`).
+    Strong(Vec<Inline>),
+    /// Strikethrough (`~~text~~` or `~text~`).
+    Strikethrough(Vec<Inline>),
+    /// Underline (`__text__` with [`Options::underline`](crate::Options::underline)).
+    Underline(Vec<Inline>),
+    /// Hyperlink with its destination, optional title and link text.
+    Link {
+        url: String,
+        title: Option<String>,
+        children: Vec<Inline>,
+    },
+    /// Image with its source, optional title and alternative text.
+    Image {
+        url: String,
+        title: Option<String>,
+        alt: String,
+    },
+    /// Reference link, resolved against the document's definitions when
+    /// rendering. Without a matching definition it renders as literal text.
+    LinkReference {
+        label: String,
+        kind: ReferenceKind,
+        children: Vec<Inline>,
+    },
+    /// Reference image, resolved like [`Inline::LinkReference`].
+    ImageReference {
+        label: String,
+        kind: ReferenceKind,
+        alt: String,
+    },
+    /// Raw inline HTML such as `<kbd>` or `<!-- comment -->`, kept verbatim.
+    Html(String),
+    /// Footnote reference (`[^label]`), numbered in order of first
+    /// reference when rendering. Without a matching definition it renders
+    /// as literal text.
+    FootnoteReference { label: String },
+    /// Line ending inside a paragraph; rendered as a newline unless
+    /// [`Options::hard_breaks`](crate::Options::hard_breaks) is set.
+    SoftBreak,
+    /// Forced line break, written as two trailing spaces or a trailing
+    /// backslash.
+    HardBreak,
+}
@@ -0,0 +1,204 @@
+//! GitHub Flavored Markdown extended autolinks.
+//!
+//! Bare `www.` and `http(s)://` URLs and email addresses in text become
+//! links. This runs on the syntax tree after parsing, so text inside links,
+//! images and code spans is never linked.
+
+use crate::ast::{Block, Inline};
+
+/// Link bare URLs and email addresses in the text of all blocks.
+pub(crate) fn linkify_blocks(blocks: &mut [Block]) {
+    for block in blocks {
+        match block {
+            Block::Heading { children, .. } | Block::Paragraph { children } => {
+                linkify_inlines(children)
+            }
+            Block::BlockQuote { children } | Block::Alert { children, .. } => {
+                linkify_blocks(children)
+            }
+            Block::FootnoteDefinition(definition) => linkify_blocks(&mut definition.children),
+            Block::List { items, .. } => {
+                for item in items {
+                    linkify_blocks(&mut item.children);
+                }
+            }
+            Block::Table { header, rows, .. } => {
+                for cell in header.iter_mut().chain(rows.iter_mut().flatten()) {
+                    linkify_inlines(&mut cell.children);
+                }
+            }
+            Block::CodeBlock { .. }
+            | Block::ThematicBreak
+            | Block::Html(_)
+            | Block::Definition(_) => {}
+        }
+    }
+}
+
+fn linkify_inlines(inlines: &mut Vec<Inline>) {
+    let mut result = Vec::with_capacity(inlines.len());
+
+    for mut inline in std::mem::take(inlines) {
+        match &mut inline {
+            Inline::Text(text) => {
+                split_text(text, &mut result);
+                continue;
+            }
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children)
+            | Inline::Underline(children) => linkify_inlines(children),
+            _ => {}
+        }
+        result.push(inline);
+    }
+
+    *inlines = result;
+}
+
+/// Split a text run into plain text and autolinks.
+fn split_text(text: &str, out: &mut Vec<Inline>) {
+    let mut plain_start = 0;
+    let mut index = 0;
+
+    while index < text.len() {
+        let previous = text[..index].chars().next_back();
+        if let Some((len, url)) = match_autolink(&text[index..], previous) {
+            if plain_start < index {
+                out.push(Inline::Text(text[plain_start..index].to_string()));
+            }
+            out.push(Inline::Link {
+                url,
+                title: None,
+                children: vec![Inline::Text(text[index..index + len].to_string())],
+            });
+            index += len;
+            plain_start = index;
+            continue;
+        }
+        index += text[index..].chars().next().map_or(1, char::len_utf8);
+    }
+
+    if plain_start < text.len() {
+        out.push(Inline::Text(text[plain_start..].to_string()));
+    }
+}
+
+/// Match an autolink at the start of `rest`, returning its length and
+/// destination. URLs must start a word: they may only follow whitespace,
+/// `*`, `_`, `~` or `(`.
+fn match_autolink(rest: &str, previous: Option<char>) -> Option<(usize, String)> {
+    let starts_word =
+        previous.is_none_or(|c| c.is_whitespace() || matches!(c, '*' | '_' | '~' | '('));
+
+    if starts_word {
+        // The domain must go on after `www.`, not end at its period
+        if rest.starts_with("www.")
+            && let Some(len) = match_url(rest, 0).filter(|&len| len > "www.".len())
+        {
+            return Some((len, format!("http://{}", &rest[..len])));
+        }
+        for scheme in ["http://", "https://"] {
+            if rest.starts_with(scheme)
+                && let Some(len) = match_url(rest, scheme.len())
+            {
+                return Some((len, rest[..len].to_string()));
+            }
+        }
+    }
+
+    if previous.is_some_and(is_email_local_char) {
+        return None;
+    }
+    match_email(rest).map(|len| (len, format!("mailto:{}", &rest[..len])))
+}
+
+/// Match a URL whose domain starts at `domain_start`. The URL runs to the
+/// next whitespace or `<`, minus trailing punctuation.
+fn match_url(rest: &str, domain_start: usize) -> Option<usize> {
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .unwrap_or(rest.len());
+    let len = trim_trailing_punctuation(&rest[..end]);
+    if len <= domain_start {
+        return None;
+    }
+
+    let domain_end = rest[domain_start..len]
+        .find(|c: char| !is_domain_char(c))
+        .map_or(len, |i| domain_start + i);
+    is_valid_domain(&rest[domain_start..domain_end]).then_some(len)
+}
+
+/// Length of `link` without trailing punctuation, unbalanced closing
+/// parentheses and trailing entity references such as `&amp;`.
+fn trim_trailing_punctuation(link: &str) -> usize {
+    let mut end = link.len();
+
+    loop {
+        let candidate = &link[..end];
+        match candidate.chars().next_back() {
+            Some('?' | '!' | '.' | ',' | ':' | '*' | '_' | '~') => end -= 1,
+            Some(')') if candidate.matches(')').count() > candidate.matches('(').count() => {
+                end -= 1
+            }
+            Some(';') => match entity_start(candidate) {
+                Some(start) => end = start,
+                None => break,
+            },
+            _ => break,
+        }
+    }
+
+    end
+}
+
+/// Start of an entity-like suffix `&name;` of `text`.
+fn entity_start(text: &str) -> Option<usize> {
+    let body = &text[..text.len() - 1];
+    let name_start = body
+        .trim_end_matches(|c: char| c.is_ascii_alphanumeric())
+        .len();
+    (name_start < body.len() && body[..name_start].ends_with('&')).then(|| name_start - 1)
+}
+
+/// A domain is made of non-empty segments separated by periods; the last
+/// two segments may not contain underscores.
+fn is_valid_domain(domain: &str) -> bool {
+    let segments: Vec<&str> = domain.split('.').collect();
+    !segments.iter().any(|segment| segment.is_empty())
+        && !segments
+            .iter()
+            .rev()
+            .take(2)
+            .any(|segment| segment.contains('_'))
+}
+
+/// Match an email address such as `user.name+tag@example.com`.
+fn match_email(rest: &str) -> Option<usize> {
+    let local_len = rest
+        .find(|c: char| !is_email_local_char(c))
+        .unwrap_or(rest.len());
+    if local_len == 0 || !rest[local_len..].starts_with('@') {
+        return None;
+    }
+
+    let domain_start = local_len + 1;
+    let domain_len = rest[domain_start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')))
+        .unwrap_or(rest.len() - domain_start);
+    let domain = rest[domain_start..domain_start + domain_len].trim_end_matches('.');
+
+    let valid = domain.contains('.')
+        && !domain.ends_with(['-', '_'])
+        && domain.split('.').all(|segment| !segment.is_empty());
+    valid.then_some(domain_start + domain.len())
+}
+
+fn is_domain_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | '-' | '_')
+}
+
+fn is_email_local_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-' | '_')
+}
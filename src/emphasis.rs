@@ -0,0 +1,196 @@
+//! Emphasis resolution with the CommonMark delimiter stack.
+//!
+//! The grammar leaves runs of `*`, `_` and `~` as delimiter tokens between
+//! the other inline elements. Whether a run can open or close emphasis
+//! depends on the characters around it, and openers are matched to closers
+//! after the whole block is tokenized, so `snake_case_names` and `2*3*4`
+//! stay literal while `***both***` nests strong emphasis inside emphasis.
+
+use std::collections::HashMap;
+
+use crate::{ast::Inline, options::Options};
+
+/// Inline content of a block before emphasis is resolved.
+pub(crate) enum InlineToken {
+    Inline(Inline),
+    Delimiter(DelimiterRun),
+}
+
+/// Run of `*`, `_` or `~` characters that may open or close emphasis.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DelimiterRun {
+    ch: char,
+    /// Characters not yet used by matched emphasis.
+    length: usize,
+    /// Length of the run as written, used by the rule of 3.
+    original: usize,
+    can_open: bool,
+    can_close: bool,
+}
+
+impl DelimiterRun {
+    /// Classify a delimiter run from the characters before and after it;
+    /// `None` stands for the start or end of the text.
+    pub(crate) fn new(run: &str, before: Option<char>, after: Option<char>) -> Self {
+        let ch = run.chars().next().unwrap_or('*');
+        let length = run.chars().count();
+
+        let before_whitespace = before.is_none_or(char::is_whitespace);
+        let after_whitespace = after.is_none_or(char::is_whitespace);
+        let before_punctuation = before.is_some_and(is_punctuation);
+        let after_punctuation = after.is_some_and(is_punctuation);
+        let left_flanking =
+            !after_whitespace && (!after_punctuation || before_whitespace || before_punctuation);
+        let right_flanking =
+            !before_whitespace && (!before_punctuation || after_whitespace || after_punctuation);
+
+        let (can_open, can_close) = match ch {
+            // Underscores inside a word never open or close emphasis
+            '_' => (
+                left_flanking && (!right_flanking || before_punctuation),
+                right_flanking && (!left_flanking || after_punctuation),
+            ),
+            // Strikethrough takes one or two tildes
+            '~' if length > 2 => (false, false),
+            _ => (left_flanking, right_flanking),
+        };
+
+        Self {
+            ch,
+            length,
+            original: length,
+            can_open,
+            can_close,
+        }
+    }
+
+    /// Whether this run can open the emphasis closed by `closer`.
+    fn opens(&self, closer: &DelimiterRun) -> bool {
+        if self.ch != closer.ch || !self.can_open {
+            return false;
+        }
+        if self.ch == '~' {
+            return self.length == closer.length;
+        }
+
+        // Rule of 3: a run that can both open and close only pairs with runs
+        // whose combined length is not a multiple of 3, unless both are.
+        let both_ways = self.can_close || closer.can_open;
+        !(both_ways
+            && (self.original + closer.original).is_multiple_of(3)
+            && !(self.original.is_multiple_of(3) && closer.original.is_multiple_of(3)))
+    }
+
+    fn text(&self) -> String {
+        std::iter::repeat_n(self.ch, self.length).collect()
+    }
+}
+
+/// Unicode punctuation or symbol character.
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || !c.is_ascii() && !c.is_alphanumeric() && !c.is_whitespace()
+}
+
+/// Delimiter runs are numbered in source order, so openers below a failed
+/// search can be skipped even after emphasis has been built around them.
+enum Node {
+    Inline(Inline),
+    Delimiter(usize, DelimiterRun),
+}
+
+/// Pair delimiter runs into emphasis, strong emphasis and strikethrough.
+/// Unmatched delimiters become literal text.
+pub(crate) fn resolve_emphasis(tokens: Vec<InlineToken>, options: &Options) -> Vec<Inline> {
+    let mut nodes: Vec<Node> = tokens
+        .into_iter()
+        .enumerate()
+        .map(|(id, token)| match token {
+            InlineToken::Inline(inline) => Node::Inline(inline),
+            InlineToken::Delimiter(run) => Node::Delimiter(id, run),
+        })
+        .collect();
+
+    // Lowest delimiter id worth searching for an opener, per closer kind
+    let mut openers_bottom: HashMap<(char, bool, usize), usize> = HashMap::new();
+    let mut closer = 0;
+
+    while closer < nodes.len() {
+        let (closer_id, closer_run) = match &nodes[closer] {
+            Node::Delimiter(id, run) if run.can_close => (*id, *run),
+            _ => {
+                closer += 1;
+                continue;
+            }
+        };
+        let kind = (closer_run.ch, closer_run.can_open, closer_run.original % 3);
+        let bottom = openers_bottom.get(&kind).copied().unwrap_or(0);
+
+        let mut opener = None;
+        for index in (0..closer).rev() {
+            if let Node::Delimiter(id, run) = &nodes[index] {
+                if *id < bottom {
+                    break;
+                }
+                if run.opens(&closer_run) {
+                    opener = Some((index, *run));
+                    break;
+                }
+            }
+        }
+
+        let Some((open, opener_run)) = opener else {
+            openers_bottom.insert(kind, closer_id);
+            if !closer_run.can_open {
+                nodes[closer] = Node::Inline(Inline::Text(closer_run.text()));
+            }
+            closer += 1;
+            continue;
+        };
+
+        let used = match closer_run.ch {
+            '~' => closer_run.length,
+            _ if opener_run.length >= 2 && closer_run.length >= 2 => 2,
+            _ => 1,
+        };
+        let children = into_inlines(nodes.drain(open + 1..closer).collect());
+        let inline = match (closer_run.ch, used) {
+            ('~', _) => Inline::Strikethrough(children),
+            ('_', 2) if options.underline => Inline::Underline(children),
+            (_, 2) => Inline::Strong(children),
+            _ => Inline::Emphasis(children),
+        };
+        nodes.insert(open + 1, Node::Inline(inline));
+        closer = open + 2;
+
+        if closer_run.length == used {
+            nodes.remove(closer);
+        } else if let Node::Delimiter(_, run) = &mut nodes[closer] {
+            run.length -= used;
+        }
+        if opener_run.length == used {
+            nodes.remove(open);
+            closer -= 1;
+        } else if let Node::Delimiter(_, run) = &mut nodes[open] {
+            run.length -= used;
+        }
+    }
+
+    into_inlines(nodes)
+}
+
+/// Turn leftover delimiters into text and merge adjacent text runs.
+fn into_inlines(nodes: Vec<Node>) -> Vec<Inline> {
+    let mut inlines: Vec<Inline> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let inline = match node {
+            Node::Inline(inline) => inline,
+            Node::Delimiter(_, run) => Inline::Text(run.text()),
+        };
+        match (inline, inlines.last_mut()) {
+            (Inline::Text(text), Some(Inline::Text(previous))) => previous.push_str(&text),
+            (inline, _) => inlines.push(inline),
+        }
+    }
+
+    inlines
+}
@@ -0,0 +1,202 @@
+//! Fields of YAML and TOML front matter.
+//!
+//! Only the subset page metadata needs is understood: `key: value` and
+//! `key = value` fields with plain or quoted strings, flow lists such as
+//! `[a, "b"]`, YAML block sequences and TOML tables. Anything else is
+//! skipped rather than reported as an error.
+
+use std::collections::HashMap;
+
+use crate::ast::{FrontMatterFormat, FrontMatterValue};
+
+/// Parse the fields of a front matter block.
+pub(crate) fn parse_fields(
+    format: FrontMatterFormat,
+    body: &str,
+) -> HashMap<String, FrontMatterValue> {
+    match format {
+        FrontMatterFormat::Yaml => parse_yaml(body),
+        FrontMatterFormat::Toml => parse_toml(body),
+    }
+}
+
+/// Top-level YAML fields. A key without a value followed by `- item` lines
+/// holds a list; other indented lines belong to nested mappings and are skipped.
+fn parse_yaml(body: &str) -> HashMap<String, FrontMatterValue> {
+    let mut fields = HashMap::new();
+    let mut list_key: Option<String> = None;
+
+    for line in body.lines() {
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with([' ', '\t', '-']) {
+            if let Some(key) = &list_key
+                && let Some(item) = content.strip_prefix('-')
+            {
+                let item = scalar(strip_comment(item.trim()));
+                match fields
+                    .entry(key.clone())
+                    .or_insert_with(|| FrontMatterValue::List(Vec::new()))
+                {
+                    FrontMatterValue::List(items) => items.push(item),
+                    FrontMatterValue::Text(_) => {}
+                }
+            }
+            continue;
+        }
+
+        list_key = None;
+        let Some((key, value)) = content.split_once(':') else {
+            continue;
+        };
+        let key = scalar(key.trim());
+        let value = strip_comment(value.trim());
+        if value.is_empty() {
+            list_key = Some(key);
+        } else {
+            fields.insert(key, value_of(value));
+        }
+    }
+
+    fields
+}
+
+/// TOML fields; keys after a `[table]` header are prefixed with its name.
+/// Arrays may span several lines.
+fn parse_toml(body: &str) -> HashMap<String, FrontMatterValue> {
+    let mut fields = HashMap::new();
+    let mut table = String::new();
+    let mut lines = body.lines();
+
+    while let Some(line) = lines.next() {
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if content.starts_with('[') {
+            table = strip_comment(content)
+                .trim_matches(['[', ']'])
+                .trim()
+                .to_string();
+            continue;
+        }
+
+        let Some((key, value)) = content.split_once('=') else {
+            continue;
+        };
+        let mut value = strip_comment(value.trim()).to_string();
+        if value.starts_with('[') {
+            while !value.ends_with(']')
+                && let Some(next) = lines.next()
+            {
+                value.push(' ');
+                value.push_str(strip_comment(next.trim()));
+            }
+        }
+
+        let key = scalar(key.trim());
+        let key = match table.as_str() {
+            "" => key,
+            table => format!("{table}.{key}"),
+        };
+        fields.insert(key, value_of(&value));
+    }
+
+    fields
+}
+
+/// Parse a field value: a `[...]` flow list or a single scalar.
+fn value_of(value: &str) -> FrontMatterValue {
+    match value
+        .strip_prefix('[')
+        .and_then(|list| list.strip_suffix(']'))
+    {
+        Some(list) => FrontMatterValue::List(
+            split_list(list)
+                .into_iter()
+                .map(|item| scalar(item.trim()))
+                .collect(),
+        ),
+        None => FrontMatterValue::Text(scalar(value)),
+    }
+}
+
+/// Split the items of a flow list at commas outside quotes.
+fn split_list(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut item_start = true;
+
+    for (index, ch) in list.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if item_start => quote = Some(ch),
+            (None, ',') => {
+                items.push(&list[start..index]);
+                start = index + 1;
+                item_start = true;
+                continue;
+            }
+            _ => {}
+        }
+        item_start &= ch.is_whitespace();
+    }
+    items.push(&list[start..]);
+
+    items.retain(|item| !item.trim().is_empty());
+    items
+}
+
+/// Remove a trailing `# comment` outside quotes. Quotes only count at the
+/// start of a value or list item, so `Don't` has no quoted part.
+fn strip_comment(value: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    let mut item_start = true;
+
+    for (index, ch) in value.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if item_start => quote = Some(ch),
+            (None, '#') if previous.is_whitespace() => return value[..index].trim_end(),
+            _ => {}
+        }
+        item_start = ch.is_whitespace() && item_start || matches!(ch, '[' | ',');
+        previous = ch;
+    }
+
+    value
+}
+
+/// Unquote a single or double quoted string; other scalars stay as written.
+fn scalar(value: &str) -> String {
+    if let Some(quoted) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return quoted.replace("''", "'");
+    }
+    let Some(quoted) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+
+    let mut result = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
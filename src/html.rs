@@ -0,0 +1,558 @@
+//! HTML rendering of the typed syntax tree.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    Options,
+    ast::{
+        AlertKind, Alignment, Block, Document, FootnoteDefinition, Inline, LinkDefinition,
+        ListItem, ListKind, ReferenceKind, TableCell, normalize_label, walk_blocks,
+    },
+};
+
+/// Render a parsed document to HTML.
+/// Each element of the result is the HTML for one top-level block.
+/// Link reference and footnote definitions produce no output in place;
+/// referenced footnotes are rendered in a final `<section class="footnotes">`.
+///
+/// # Arguments
+/// * `document` - Parsed document to render
+///
+/// # Returns
+/// Vector of HTML strings
+pub fn render_html(document: &Document) -> Vec<String> {
+    render_html_with_options(document, &Options::default())
+}
+
+/// Render a parsed document to HTML with rendering options applied.
+///
+/// # Arguments
+/// * `document` - Parsed document to render
+/// * `options` - Rendering options, e.g. raw HTML passthrough
+///
+/// # Returns
+/// Vector of HTML strings
+pub fn render_html_with_options(document: &Document, options: &Options) -> Vec<String> {
+    let renderer = HtmlRenderer::new(document, options);
+    let mut html = renderer.render_blocks(&document.blocks);
+    html.extend(renderer.render_footnotes());
+    html
+}
+
+/// Renderer state shared by all blocks of a document.
+struct HtmlRenderer<'a> {
+    options: &'a Options,
+    /// Link reference definitions by normalized label.
+    definitions: HashMap<String, &'a LinkDefinition>,
+    /// Footnote definitions by normalized label.
+    footnote_definitions: HashMap<String, &'a FootnoteDefinition>,
+    /// Referenced footnotes in order of first reference, with the number of
+    /// references rendered so far.
+    footnotes: RefCell<Vec<(&'a FootnoteDefinition, usize)>>,
+    /// Heading ids in use, with the number of duplicates of each slug.
+    heading_ids: RefCell<HashMap<String, usize>>,
+}
+
+impl<'a> HtmlRenderer<'a> {
+    fn new(document: &'a Document, options: &'a Options) -> Self {
+        // Explicit ids are reserved up front so generated slugs avoid them
+        let mut heading_ids = HashMap::new();
+        walk_blocks(&document.blocks, &mut |block| {
+            if let Block::Heading { id: Some(id), .. } = block {
+                heading_ids.insert(id.clone(), 0);
+            }
+        });
+
+        Self {
+            options,
+            definitions: document.definitions(),
+            footnote_definitions: document.footnote_definitions(),
+            footnotes: RefCell::new(Vec::new()),
+            heading_ids: RefCell::new(heading_ids),
+        }
+    }
+
+    /// Render blocks, skipping definitions which produce no output in place.
+    fn render_blocks(&self, blocks: &[Block]) -> Vec<String> {
+        blocks
+            .iter()
+            .filter(|block| !is_definition(block))
+            .map(|block| self.convert_to_html(block))
+            .collect()
+    }
+
+    /// Convert a single block to its HTML representation.
+    /// This is the core rendering dispatcher for different markdown elements.
+    fn convert_to_html(&self, block: &Block) -> String {
+        match block {
+            Block::Heading {
+                level,
+                id,
+                children,
+            } => self.render_heading(*level, id.as_deref(), children),
+            Block::Paragraph { children } => format!("<p>{}</p>", self.render_inlines(children)),
+            Block::BlockQuote { children } => self.render_quote(children),
+            Block::Alert {
+                kind,
+                title,
+                children,
+            } => self.render_alert(*kind, title.as_deref(), children),
+            Block::List { kind, tight, items } => self.render_list(*kind, *tight, items),
+            Block::CodeBlock { info, literal } => render_code_block(info, literal),
+            Block::ThematicBreak => "<hr>".to_string(),
+            Block::Html(html) if self.options.unsafe_html => html.clone(),
+            Block::Html(html) => format!("<p>{}</p>", html_escape::encode_text(html)),
+            Block::Definition(_) | Block::FootnoteDefinition(_) => String::new(),
+            Block::Table {
+                alignments,
+                header,
+                rows,
+            } => self.render_table(alignments, header, rows),
+        }
+    }
+
+    /// Render a heading with its explicit or generated id and, when enabled,
+    /// a permalink anchor.
+    fn render_heading(&self, level: u8, id: Option<&str>, children: &[Inline]) -> String {
+        let content = self.render_inlines(children);
+        let Some(id) = id
+            .map(str::to_string)
+            .or_else(|| self.heading_slug(children))
+        else {
+            return format!("<h{level}>{content}</h{level}>");
+        };
+
+        let id = html_escape::encode_double_quoted_attribute(&id);
+        let anchor = if self.options.heading_anchors {
+            format!("<a class=\"anchor\" href=\"#{id}\" aria-hidden=\"true\"></a>")
+        } else {
+            String::new()
+        };
+        format!("<h{level} id=\"{id}\">{anchor}{content}</h{level}>")
+    }
+
+    /// Unique GitHub-style slug of a heading: repeated slugs get `-1`, `-2`, ...
+    /// suffixes. Headings whose slug is empty, e.g. only punctuation, get no id.
+    fn heading_slug(&self, children: &[Inline]) -> Option<String> {
+        let slug = slugify(&plain_text(children));
+        if slug.is_empty() {
+            return None;
+        }
+
+        let mut ids = self.heading_ids.borrow_mut();
+        let mut id = slug.clone();
+        while ids.contains_key(&id) {
+            let count = ids.entry(slug.clone()).or_insert(0);
+            *count += 1;
+            id = format!("{slug}-{count}");
+        }
+        ids.insert(id.clone(), 0);
+        Some(id)
+    }
+
+    fn render_quote(&self, children: &[Block]) -> String {
+        let lines = self.render_blocks(children);
+        format!("<blockquote>\n{}\n</blockquote>", lines.join("\n"))
+    }
+
+    /// Render an alert as a `markdown-alert` div led by its title, as GitHub does.
+    fn render_alert(&self, kind: AlertKind, title: Option<&str>, children: &[Block]) -> String {
+        let (class, default_title) = match kind {
+            AlertKind::Note => ("note", "Note"),
+            AlertKind::Tip => ("tip", "Tip"),
+            AlertKind::Important => ("important", "Important"),
+            AlertKind::Warning => ("warning", "Warning"),
+            AlertKind::Caution => ("caution", "Caution"),
+        };
+        let mut lines = vec![format!(
+            "<p class=\"markdown-alert-title\">{}</p>",
+            html_escape::encode_text(title.unwrap_or(default_title))
+        )];
+        lines.extend(self.render_blocks(children));
+
+        format!(
+            "<div class=\"markdown-alert markdown-alert-{class}\">\n{}\n</div>",
+            lines.join("\n")
+        )
+    }
+
+    fn render_list(&self, kind: ListKind, tight: bool, items: &[ListItem]) -> String {
+        let (tag, attrs) = match kind {
+            ListKind::Bullet(_) => ("ul", String::new()),
+            ListKind::Ordered { start: 1, .. } => ("ol", String::new()),
+            ListKind::Ordered { start, .. } => ("ol", format!(" start=\"{start}\"")),
+        };
+        let items: Vec<String> = items
+            .iter()
+            .map(|item| self.render_list_item(item, tight))
+            .collect();
+
+        format!("<{tag}{attrs}>\n{}\n</{tag}>", items.join("\n"))
+    }
+
+    /// Render a list item. Paragraphs of tight lists are emitted without `<p>`,
+    /// other blocks go on their own lines. Task items start with a disabled checkbox.
+    fn render_list_item(&self, item: &ListItem, tight: bool) -> String {
+        let mut html = String::from("<li>");
+        let mut checkbox = match item.checked {
+            Some(true) => "<input type=\"checkbox\" disabled checked> ",
+            Some(false) => "<input type=\"checkbox\" disabled> ",
+            None => "",
+        };
+        let mut bare_last = true;
+
+        for block in &item.children {
+            match block {
+                block if is_definition(block) => continue,
+                Block::Paragraph { children } if tight => {
+                    if !bare_last {
+                        html.push('\n');
+                    }
+                    html.push_str(checkbox);
+                    html.push_str(&self.render_inlines(children));
+                    bare_last = true;
+                }
+                Block::Paragraph { children } => {
+                    html.push_str(&format!(
+                        "\n<p>{checkbox}{}</p>",
+                        self.render_inlines(children)
+                    ));
+                    bare_last = false;
+                }
+                _ => {
+                    html.push_str(checkbox.trim_end());
+                    html.push('\n');
+                    html.push_str(&self.convert_to_html(block));
+                    bare_last = false;
+                }
+            }
+            checkbox = "";
+        }
+
+        html.push_str(checkbox.trim_end());
+        if !bare_last {
+            html.push('\n');
+        }
+        html.push_str("</li>");
+        html
+    }
+
+    fn render_table(
+        &self,
+        alignments: &[Alignment],
+        header: &[TableCell],
+        rows: &[Vec<TableCell>],
+    ) -> String {
+        let mut html = String::from("<table>\n<thead>\n");
+        html.push_str(&self.render_table_row("th", alignments, header));
+        html.push_str("</thead>\n");
+
+        if !rows.is_empty() {
+            html.push_str("<tbody>\n");
+            for row in rows {
+                html.push_str(&self.render_table_row("td", alignments, row));
+            }
+            html.push_str("</tbody>\n");
+        }
+
+        html.push_str("</table>");
+        html
+    }
+
+    fn render_table_row(&self, tag: &str, alignments: &[Alignment], cells: &[TableCell]) -> String {
+        let mut html = String::from("<tr>\n");
+        for (cell, alignment) in cells.iter().zip(alignments) {
+            let align = match alignment {
+                Alignment::None => "",
+                Alignment::Left => " align=\"left\"",
+                Alignment::Center => " align=\"center\"",
+                Alignment::Right => " align=\"right\"",
+            };
+            html.push_str(&format!(
+                "<{tag}{align}>{}</{tag}>\n",
+                self.render_inlines(&cell.children)
+            ));
+        }
+        html.push_str("</tr>\n");
+        html
+    }
+
+    fn render_inlines(&self, inlines: &[Inline]) -> String {
+        inlines
+            .iter()
+            .map(|inline| self.render_inline(inline))
+            .collect()
+    }
+
+    /// Render inline elements (text, formatting, links, images).
+    fn render_inline(&self, inline: &Inline) -> String {
+        match inline {
+            Inline::Text(text) => html_escape::encode_text(text).to_string(),
+            Inline::Code(code) => format!("<code>{}</code>", html_escape::encode_text(code)),
+            Inline::Html(html) if self.options.unsafe_html => html.clone(),
+            Inline::Html(html) => html_escape::encode_text(html).to_string(),
+            Inline::Emphasis(children) => format!("<em>{}</em>", self.render_inlines(children)),
+            Inline::Strong(children) => {
+                format!("<strong>{}</strong>", self.render_inlines(children))
+            }
+            Inline::Strikethrough(children) => {
+                format!("<del>{}</del>", self.render_inlines(children))
+            }
+            Inline::Underline(children) => format!("<u>{}</u>", self.render_inlines(children)),
+            Inline::Link {
+                url,
+                title,
+                children,
+            } => render_link(url, title.as_deref(), &self.render_inlines(children)),
+            Inline::Image { url, title, alt } => render_image(url, title.as_deref(), alt),
+            Inline::LinkReference {
+                label,
+                kind,
+                children,
+            } => {
+                let text = self.render_inlines(children);
+                match self.definitions.get(&normalize_label(label)) {
+                    Some(definition) => {
+                        render_link(&definition.url, definition.title.as_deref(), &text)
+                    }
+                    None => format!("[{text}]{}", render_reference_suffix(label, *kind)),
+                }
+            }
+            Inline::ImageReference { label, kind, alt } => {
+                match self.definitions.get(&normalize_label(label)) {
+                    Some(definition) => {
+                        render_image(&definition.url, definition.title.as_deref(), alt)
+                    }
+                    None => format!(
+                        "![{}]{}",
+                        html_escape::encode_text(alt),
+                        render_reference_suffix(label, *kind)
+                    ),
+                }
+            }
+            Inline::FootnoteReference { label } => {
+                match self.footnote_definitions.get(&normalize_label(label)) {
+                    Some(definition) => self.render_footnote_reference(definition),
+                    None => format!("[^{}]", html_escape::encode_text(label)),
+                }
+            }
+            Inline::SoftBreak if self.options.hard_breaks => "<br>\n".to_string(),
+            Inline::SoftBreak => "\n".to_string(),
+            Inline::HardBreak => "<br>\n".to_string(),
+        }
+    }
+
+    /// Render a superscript link to a footnote, numbering the footnote on
+    /// its first reference.
+    fn render_footnote_reference(&self, definition: &'a FootnoteDefinition) -> String {
+        let mut footnotes = self.footnotes.borrow_mut();
+        let index = match footnotes
+            .iter()
+            .position(|(footnote, _)| std::ptr::eq(*footnote, definition))
+        {
+            Some(index) => index,
+            None => {
+                footnotes.push((definition, 0));
+                footnotes.len() - 1
+            }
+        };
+        footnotes[index].1 += 1;
+
+        let id = html_escape::encode_double_quoted_attribute(&definition.label);
+        format!(
+            "<sup class=\"footnote-ref\"><a href=\"#fn-{id}\" id=\"{}\" data-footnote-ref>{}</a></sup>",
+            footnote_reference_id(&id, footnotes[index].1),
+            index + 1
+        )
+    }
+
+    /// Render the section listing all referenced footnotes, each followed by
+    /// back-references to every place it was referenced from.
+    fn render_footnotes(&self) -> Option<String> {
+        let mut bodies = Vec::new();
+        // Footnote bodies may reference further footnotes, which are
+        // appended to the list while rendering
+        loop {
+            let next = self.footnotes.borrow().get(bodies.len()).map(|(d, _)| *d);
+            let Some(definition) = next else { break };
+            bodies.push(self.render_footnote_body(definition));
+        }
+        if bodies.is_empty() {
+            return None;
+        }
+
+        let footnotes = self.footnotes.borrow();
+        let items: Vec<String> = bodies
+            .into_iter()
+            .zip(footnotes.iter())
+            .enumerate()
+            .map(
+                |(index, ((mut blocks, paragraph), (definition, references)))| {
+                    let id = html_escape::encode_double_quoted_attribute(&definition.label);
+                    let backrefs: Vec<String> = (1..=*references)
+                        .map(|reference| render_backref(&id, index + 1, reference))
+                        .collect();
+                    let backrefs = backrefs.join(" ");
+                    blocks.push(match paragraph {
+                        Some(text) if !text.is_empty() => format!("<p>{text} {backrefs}</p>"),
+                        _ => format!("<p>{backrefs}</p>"),
+                    });
+                    format!("<li id=\"fn-{id}\">\n{}\n</li>", blocks.join("\n"))
+                },
+            )
+            .collect();
+
+        Some(format!(
+            "<section class=\"footnotes\" data-footnotes>\n<ol>\n{}\n</ol>\n</section>",
+            items.join("\n")
+        ))
+    }
+
+    /// Render the blocks of a footnote. A trailing paragraph is returned
+    /// separately as inline HTML so back-references can be appended to it.
+    fn render_footnote_body(
+        &self,
+        definition: &FootnoteDefinition,
+    ) -> (Vec<String>, Option<String>) {
+        let children: Vec<&Block> = definition
+            .children
+            .iter()
+            .filter(|block| !is_definition(block))
+            .collect();
+
+        match children.split_last() {
+            Some((Block::Paragraph { children: last }, rest)) => {
+                let blocks = rest
+                    .iter()
+                    .map(|block| self.convert_to_html(block))
+                    .collect();
+                (blocks, Some(self.render_inlines(last)))
+            }
+            _ => (
+                children
+                    .iter()
+                    .map(|block| self.convert_to_html(block))
+                    .collect(),
+                None,
+            ),
+        }
+    }
+}
+
+/// Definitions produce no output where they are written.
+fn is_definition(block: &Block) -> bool {
+    matches!(block, Block::Definition(_) | Block::FootnoteDefinition(_))
+}
+
+/// Text content of inline elements, without markup or image alt text.
+fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(content) | Inline::Code(content) => text.push_str(content),
+            Inline::Emphasis(children)
+            | Inline::Strong(children)
+            | Inline::Strikethrough(children)
+            | Inline::Underline(children)
+            | Inline::Link { children, .. }
+            | Inline::LinkReference { children, .. } => text.push_str(&plain_text(children)),
+            Inline::SoftBreak => text.push(' '),
+            Inline::Image { .. }
+            | Inline::ImageReference { .. }
+            | Inline::Html(_)
+            | Inline::FootnoteReference { .. }
+            | Inline::HardBreak => {}
+        }
+    }
+    text
+}
+
+/// GitHub-compatible slug: lowercased, with spaces turned into hyphens and
+/// everything except letters, digits, `-` and `_` removed.
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Element id of the `reference`-th reference to a footnote.
+fn footnote_reference_id(id: &str, reference: usize) -> String {
+    match reference {
+        1 => format!("fnref-{id}"),
+        _ => format!("fnref-{id}-{reference}"),
+    }
+}
+
+/// Link from a footnote back to one of its references.
+fn render_backref(id: &str, number: usize, reference: usize) -> String {
+    let target = footnote_reference_id(id, reference);
+    match reference {
+        1 => format!(
+            "<a href=\"#{target}\" class=\"footnote-backref\" aria-label=\"Back to reference {number}\">↩</a>"
+        ),
+        _ => format!(
+            "<a href=\"#{target}\" class=\"footnote-backref\" aria-label=\"Back to reference {number}-{reference}\">↩<sup class=\"footnote-ref\">{reference}</sup></a>"
+        ),
+    }
+}
+
+/// Render fenced code blocks with optional language class.
+fn render_code_block(info: &str, literal: &str) -> String {
+    let language = info.split_whitespace().next().unwrap_or("");
+    let lang_attr = if language.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " class=\"language-{}\"",
+            html_escape::encode_double_quoted_attribute(language)
+        )
+    };
+
+    format!(
+        "<pre><code{lang_attr}>{}</code></pre>",
+        html_escape::encode_text(literal)
+    )
+}
+
+fn render_link(url: &str, title: Option<&str>, text: &str) -> String {
+    format!(
+        "<a href=\"{}\"{}>{text}</a>",
+        html_escape::encode_double_quoted_attribute(url),
+        render_title(title)
+    )
+}
+
+fn render_image(url: &str, title: Option<&str>, alt: &str) -> String {
+    format!(
+        "<img src=\"{}\" alt=\"{}\"{}>",
+        html_escape::encode_double_quoted_attribute(url),
+        html_escape::encode_double_quoted_attribute(alt),
+        render_title(title)
+    )
+}
+
+/// Source text following the brackets of an unresolved reference.
+fn render_reference_suffix(label: &str, kind: ReferenceKind) -> String {
+    match kind {
+        ReferenceKind::Full => format!("[{}]", html_escape::encode_text(label)),
+        ReferenceKind::Collapsed => "[]".to_string(),
+        ReferenceKind::Shortcut => String::new(),
+    }
+}
+
+fn render_title(title: Option<&str>) -> String {
+    title
+        .map(|t| {
+            format!(
+                " title=\"{}\"",
+                html_escape::encode_double_quoted_attribute(t)
+            )
+        })
+        .unwrap_or_default()
+}
@@ -6,6 +6,7 @@
 //! ## Features
 //!
 //! - Full Markdown syntax support (headings, paragraphs, links, images, formatting)
+//! - Typed syntax tree ([`Document`], [`Block`], [`Inline`]) independent of grammar rules
 //! - Robust error handling with custom error types
 //! - HTML output generation with proper escaping
 //! - Command-line interface integration
@@ -18,20 +19,38 @@
 //! let markdown = "# Hello World\n\nThis is **bold** text.";
 //! let html = str_to_html(markdown).unwrap();
 //! println!("{}", html.join("\n"));
+//!
+//! let document = parse_markdown(markdown).unwrap();
+//! assert_eq!(document.blocks.len(), 2);
 //! ```
 
+pub mod ast;
+mod autolink;
+mod emphasis;
+mod front_matter;
+mod html;
+mod options;
+
 use std::{
+    borrow::Cow,
     fs::{File, OpenOptions},
     io::{BufRead, BufReader, Write},
     path::Path,
 };
 
-use pest::{
-    Parser,
-    iterators::{Pair, Pairs},
-};
+use pest::{Parser, iterators::Pair};
 use pest_derive::Parser;
 
+use emphasis::{DelimiterRun, InlineToken, resolve_emphasis};
+
+pub use ast::{
+    AlertKind, Alignment, Block, Document, FootnoteDefinition, FrontMatter, FrontMatterFormat,
+    FrontMatterValue, Inline, LinkDefinition, ListItem, ListKind, ReferenceKind, TableCell,
+    normalize_label,
+};
+pub use html::{render_html, render_html_with_options};
+pub use options::Options;
+
 /// Custom error type for markdown parsing operations.
 /// Provides detailed error information for different failure scenarios.
 #[derive(Debug, thiserror::Error)]
@@ -48,16 +67,109 @@ pub enum MarkdownError {
 pub struct MarkdownParser;
 
 /// Main parsing function that processes markdown input.
-/// Returns parsed syntax tree or error if parsing fails.
+/// Returns the typed syntax tree or error if parsing fails.
+///
+/// # Arguments
+/// * `input` - Raw markdown text as string slice
+///
+/// # Returns
+/// Result containing parsed Document or MarkdownError
+pub fn parse_markdown(input: &str) -> Result<Document, MarkdownError> {
+    parse_markdown_with_options(input, &Options::default())
+}
+
+/// Parse markdown with optional syntax extensions enabled.
 ///
 /// # Arguments
 /// * `input` - Raw markdown text as string slice
+/// * `options` - Extensions to enable
 ///
 /// # Returns
-/// Result containing parsed pairs or MarkdownError
-pub fn parse_markdown(input: &str) -> Result<Pairs<'_, Rule>, MarkdownError> {
-    MarkdownParser::parse(Rule::document_structure, input)
-        .map_err(|e| MarkdownError::ParseError(e.to_string()))
+/// Result containing parsed Document or MarkdownError
+pub fn parse_markdown_with_options(
+    input: &str,
+    options: &Options,
+) -> Result<Document, MarkdownError> {
+    let (front_matter, body) = split_front_matter(input);
+    let mut blocks = parse_blocks(body, options)?;
+    if options.extended_autolinks {
+        autolink::linkify_blocks(&mut blocks);
+    }
+
+    Ok(Document {
+        front_matter,
+        blocks,
+    })
+}
+
+/// Split YAML (`---`) or TOML (`+++`) front matter off the start of the
+/// input, returning its parsed fields and the remaining markdown.
+fn split_front_matter(input: &str) -> (Option<FrontMatter>, &str) {
+    let Some(pair) = MarkdownParser::parse(Rule::front_matter, input)
+        .ok()
+        .and_then(|mut pairs| pairs.next())
+    else {
+        return (None, input);
+    };
+
+    let format = if input.starts_with("+++") {
+        FrontMatterFormat::Toml
+    } else {
+        FrontMatterFormat::Yaml
+    };
+    let rest = &input[pair.as_str().len()..];
+    let raw = pair
+        .into_inner()
+        .next()
+        .map_or("", |body| body.as_str())
+        .to_string();
+    let fields = front_matter::parse_fields(format, &raw);
+
+    (
+        Some(FrontMatter {
+            format,
+            raw,
+            fields,
+        }),
+        rest,
+    )
+}
+
+/// Parse markdown text into blocks.
+/// Also used to re-parse the dedented contents of container blocks.
+fn parse_blocks(input: &str, options: &Options) -> Result<Vec<Block>, MarkdownError> {
+    parse_blocks_with_gaps(input, options).map(|(blocks, _)| blocks)
+}
+
+/// Parse markdown text into blocks, also reporting whether
+/// any two consecutive blocks are separated by a blank line.
+fn parse_blocks_with_gaps(
+    input: &str,
+    options: &Options,
+) -> Result<(Vec<Block>, bool), MarkdownError> {
+    let mut parsed = MarkdownParser::parse(Rule::document_structure, input)
+        .map_err(|e| MarkdownError::ParseError(e.to_string()))?;
+    let document = parsed
+        .next()
+        .ok_or_else(|| MarkdownError::ParseError("Empty document".to_string()))?;
+
+    let mut blocks = Vec::new();
+    let mut separated = false;
+    let mut previous_end = None;
+
+    for pair in document.into_inner() {
+        if pair.as_rule() == Rule::EOI {
+            continue;
+        }
+        let span = pair.as_span();
+        if let Some(end) = previous_end {
+            separated |= input[end..span.start()].contains('\n');
+        }
+        previous_end = Some(span.end());
+        blocks.push(process_block(pair, options)?);
+    }
+
+    Ok((blocks, separated))
 }
 
 /// Convert markdown string to vector of HTML strings.
@@ -69,48 +181,79 @@ pub fn parse_markdown(input: &str) -> Result<Pairs<'_, Rule>, MarkdownError> {
 /// # Returns
 /// Vector of HTML strings or MarkdownError
 pub fn str_to_html(input: &str) -> Result<Vec<String>, MarkdownError> {
-    let mut parsed = parse_markdown(input)?;
-    let document = parsed
-        .next()
-        .ok_or_else(|| MarkdownError::ParseError("Empty document".to_string()))?;
+    str_to_html_with_options(input, &Options::default())
+}
 
-    let results: Result<Vec<String>, MarkdownError> = document
-        .into_inner()
-        .filter(|pair| !matches!(pair.as_rule(), Rule::EOI))
-        .map(convert_to_html)
-        .collect();
+/// Convert markdown string to vector of HTML strings with optional
+/// extensions enabled.
+///
+/// # Arguments
+/// * `input` - Markdown text to convert
+/// * `options` - Extensions to enable
+///
+/// # Returns
+/// Vector of HTML strings or MarkdownError
+pub fn str_to_html_with_options(
+    input: &str,
+    options: &Options,
+) -> Result<Vec<String>, MarkdownError> {
+    let document = parse_markdown_with_options(input, options)?;
+    Ok(render_html_with_options(&document, options))
+}
 
-    results
+/// Convert markdown string to HTML, also returning the document's front
+/// matter so callers can read metadata such as the title or tags.
+///
+/// # Arguments
+/// * `input` - Markdown text to convert
+/// * `options` - Extensions to enable
+///
+/// # Returns
+/// Front matter (if any) and vector of HTML strings, or MarkdownError
+pub fn str_to_html_with_front_matter(
+    input: &str,
+    options: &Options,
+) -> Result<(Option<FrontMatter>, Vec<String>), MarkdownError> {
+    let document = parse_markdown_with_options(input, options)?;
+    let html = render_html_with_options(&document, options);
+    Ok((document.front_matter, html))
 }
 
-/// Convert a single parsed rule to HTML representation.
-/// This is the core conversion dispatcher for different markdown elements.
+/// Convert a single parsed rule to a syntax tree block.
+/// This is the core dispatcher for different markdown block elements.
 ///
 /// # Arguments
 /// * `pair` - Pest Pair representing parsed rule
+/// * `options` - Extensions to enable
 ///
 /// # Returns
-/// HTML string or MarkdownError
-fn convert_to_html(pair: Pair<Rule>) -> Result<String, MarkdownError> {
+/// Block or MarkdownError
+fn process_block(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
     match pair.as_rule() {
         Rule::document_block => {
             let inner = pair.into_inner().next().unwrap();
-            convert_to_html(inner)
+            process_block(inner, options)
         }
-        Rule::document_heading => process_document_heading(pair),
-        Rule::h1_heading | Rule::h2_heading | Rule::h3_heading | Rule::h4_heading | Rule::h5_heading | Rule::h6_heading => process_heading(pair),
-        Rule::document_paragraph => process_document_paragraph(pair),
-        Rule::document_quote => process_document_quote(pair),
-        Rule::quote_line => process_quote_line(pair),
-        Rule::paragraph_text => process_paragraph_text(pair),
-        Rule::document_unordered_list => process_unordered_list(pair),
-        Rule::document_ordered_list => process_ordered_list(pair),
-        Rule::unordered_list_item => process_list_item(pair),
-        Rule::ordered_list_item => process_list_item(pair),
-        Rule::code_fence => process_code_fence(pair),
-        Rule::thematic_break => Ok("<hr>".to_string()),
-        Rule::blank_line => Ok("<br>".to_string()),
-        Rule::EOI => Ok(String::new()),
+        Rule::h1_heading
+        | Rule::h2_heading
+        | Rule::h3_heading
+        | Rule::h4_heading
+        | Rule::h5_heading
+        | Rule::h6_heading => process_heading(pair, options),
+        Rule::setext_heading => process_setext_heading(pair, options),
+        Rule::document_paragraph => process_paragraph(pair, options),
+        Rule::document_quote => process_quote(pair, options),
+        Rule::admonition => process_admonition(pair, options),
+        Rule::document_unordered_list | Rule::document_ordered_list => process_list(pair, options),
+        Rule::code_fence => process_code_block(pair),
+        Rule::indented_code => process_indented_code(pair),
+        Rule::thematic_break => Ok(Block::ThematicBreak),
+        Rule::document_table => process_table(pair, options),
+        Rule::link_reference_definition => process_link_definition(pair),
+        Rule::footnote_definition => process_footnote_definition(pair, options),
+        Rule::html_block => Ok(Block::Html(
+            pair.as_str().trim_end_matches(['\r', '\n']).to_string(),
+        )),
         _ => Err(MarkdownError::ParseError(format!(
             "Unknown rule: {:?}",
             pair.as_rule()
@@ -118,14 +261,8 @@ fn convert_to_html(pair: Pair<Rule>) -> Result<String, MarkdownError> {
     }
 }
 
-/// Process document heading container.
-fn process_document_heading(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let inner = pair.into_inner().next().unwrap();
-    process_heading(inner)
-}
-
 /// Process heading elements (H1, H2, H3, H4, H5, H6).
-fn process_heading(pair: Pair<Rule>) -> Result<String, MarkdownError> {
+fn process_heading(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
     let level = match pair.as_rule() {
         Rule::h1_heading => 1,
         Rule::h2_heading => 2,
@@ -136,258 +273,790 @@ fn process_heading(pair: Pair<Rule>) -> Result<String, MarkdownError> {
         _ => return Err(MarkdownError::ParseError("Invalid heading".to_string())),
     };
 
-    let content = pair.as_str();
-    let text = content
-        .trim_start_matches('#')
-        .trim_start_matches(char::is_whitespace)
-        .trim_end_matches('\n')
-        .trim();
+    let mut id = None;
+    let mut children = Vec::new();
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::heading_text => children = process_inline_text(inner, options)?,
+            Rule::heading_id => id = Some(inner.as_str().to_string()),
+            _ => {}
+        }
+    }
 
-    Ok(format!(
-        "<h{level}>{}</h{level}>",
-        html_escape::encode_text(text)
-    ))
+    Ok(Block::Heading {
+        level,
+        id,
+        children,
+    })
 }
 
-fn process_document_paragraph(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    process_paragraph(pair)
+/// Process setext headings; `=` underlines give level 1, `-` underlines level 2.
+fn process_setext_heading(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    let mut level = 1;
+    let mut id = None;
+    let mut children = Vec::new();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::setext_text => children = process_paragraph_lines(inner, options)?,
+            Rule::heading_id => id = Some(inner.as_str().to_string()),
+            Rule::setext_underline if inner.as_str().trim_start().starts_with('-') => {
+                level = 2;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Block::Heading {
+        level,
+        id,
+        children,
+    })
 }
 
-fn process_paragraph(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let content: Result<String, MarkdownError> = pair
-        .into_inner()
-        .map(|line| process_paragraph_line(line))
-        .collect();
+/// Process single-line inline text of headings and list items,
+/// dropping trailing whitespace.
+fn process_inline_text(pair: Pair<Rule>, options: &Options) -> Result<Vec<Inline>, MarkdownError> {
+    let mut children = process_inlines(pair.into_inner(), options)?;
+    trim_block_end(&mut children);
+    Ok(children)
+}
 
-    Ok(format!("<p>{}</p>", content?))
+/// Drop whitespace at the end of a block's inline content. A backslash
+/// ending the block is not a hard break but a literal backslash.
+fn trim_block_end(children: &mut Vec<Inline>) {
+    if let Some(Inline::HardBreak) = children.last() {
+        children.pop();
+        push_text(children, "\\");
+    } else if let Some(Inline::Text(text)) = children.last_mut() {
+        text.truncate(text.trim_end().len());
+        if text.is_empty() {
+            children.pop();
+        }
+    }
 }
 
-fn process_paragraph_text(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
-        .map(|inline| process_inline_element(inline))
-        .collect()
+/// Append text, merging it into a preceding text run.
+fn push_text(children: &mut Vec<Inline>, text: &str) {
+    match children.last_mut() {
+        Some(Inline::Text(previous)) => previous.push_str(text),
+        _ => children.push(Inline::Text(text.to_string())),
+    }
 }
 
-fn process_paragraph_line(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
-        .map(|inline| process_inline_element(inline))
-        .collect()
+fn process_paragraph(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    Ok(Block::Paragraph {
+        children: process_paragraph_lines(pair, options)?,
+    })
 }
 
-/// Process inline elements (text, formatting, links, images).
-fn process_inline_element(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    match pair.as_rule() {
-        Rule::plain_text => Ok(html_escape::encode_text(pair.as_str()).to_string()),
-        Rule::inline_code => {
-            let full = pair.as_str();
-            let code = full
-                .strip_prefix('`')
-                .and_then(|s| s.strip_suffix('`'))
-                .unwrap_or("");
-            Ok(format!("<code>{}</code>", html_escape::encode_text(code)))
+/// Process the lines of a paragraph. Lines ending in two or more spaces or
+/// a backslash end with a hard break, other line endings are soft breaks.
+/// Emphasis is resolved over the whole paragraph, so it may span lines.
+fn process_paragraph_lines(
+    pair: Pair<Rule>,
+    options: &Options,
+) -> Result<Vec<Inline>, MarkdownError> {
+    let mut tokens = Vec::new();
+    for (index, line) in pair.into_inner().enumerate() {
+        if index > 0 {
+            let line_ending = take_line_ending(&mut tokens);
+            tokens.push(InlineToken::Inline(line_ending));
         }
-        Rule::link => process_link(pair),
-        Rule::image => process_image(pair),
-        Rule::bold_formatting => {
-            let content = process_bold_content(pair)?;
-            Ok(format!("<strong>{content}</strong>"))
-        }
-        Rule::italic_formatting => {
-            let content = process_italic_content(pair)?;
-            Ok(format!("<em>{content}</em>"))
-        }
-        Rule::strikethrough_formatting => {
-            let content = process_strikethrough_content(pair)?;
-            Ok(format!("<del>{content}</del>"))
+        tokens.extend(process_paragraph_text(line, options)?);
+    }
+
+    let mut children = resolve_emphasis(tokens, options);
+    trim_block_end(&mut children);
+    Ok(children)
+}
+
+/// Remove the trailing whitespace or backslash of a line, returning the
+/// break that ends the line.
+fn take_line_ending(tokens: &mut Vec<InlineToken>) -> Inline {
+    match tokens.last_mut() {
+        Some(InlineToken::Inline(Inline::HardBreak)) => {
+            tokens.pop();
+            Inline::HardBreak
         }
-        Rule::underline_formatting => {
-            let content = process_underline_content(pair)?;
-            Ok(format!("<u>{content}</u>"))
+        Some(InlineToken::Inline(Inline::Text(text))) => {
+            let content_len = text.trim_end().len();
+            let hard = text[content_len..].matches(' ').count() >= 2;
+            text.truncate(content_len);
+            if text.is_empty() {
+                tokens.pop();
+            }
+            if hard {
+                Inline::HardBreak
+            } else {
+                Inline::SoftBreak
+            }
         }
-        Rule::text_formatting => process_text_formatting(pair),
-        Rule::escape_sequence => process_escape_sequence(pair),
-        _ => Ok(html_escape::encode_text(pair.as_str()).to_string()),
+        _ => Inline::SoftBreak,
     }
 }
 
-/// Process text formatting (bold, italic, strikethrough, underline).
-fn process_text_formatting(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let rule = pair.as_rule();
-    match rule {
-        Rule::bold_formatting => {
-            let content = process_bold_content(pair)?;
-            Ok(format!("<strong>{content}</strong>"))
-        }
-        Rule::italic_formatting => {
-            let content = process_italic_content(pair)?;
-            Ok(format!("<em>{content}</em>"))
+/// Tokenize one paragraph line; leading indentation of the line is dropped.
+fn process_paragraph_text(
+    pair: Pair<Rule>,
+    options: &Options,
+) -> Result<Vec<InlineToken>, MarkdownError> {
+    let mut tokens = inline_tokens(pair.into_inner(), options)?;
+    if let Some(InlineToken::Inline(Inline::Text(text))) = tokens.first_mut() {
+        *text = text.trim_start().to_string();
+        if text.is_empty() {
+            tokens.remove(0);
         }
-        Rule::strikethrough_formatting => {
-            let content = process_strikethrough_content(pair)?;
-            Ok(format!("<del>{content}</del>"))
+    }
+
+    Ok(tokens)
+}
+
+/// Process a sequence of inline elements, resolving emphasis and merging
+/// adjacent text runs.
+fn process_inlines<'a>(
+    pairs: impl Iterator<Item = Pair<'a, Rule>>,
+    options: &Options,
+) -> Result<Vec<Inline>, MarkdownError> {
+    Ok(resolve_emphasis(inline_tokens(pairs, options)?, options))
+}
+
+/// Convert inline elements to tokens, keeping emphasis delimiter runs apart
+/// together with the characters around them.
+fn inline_tokens<'a>(
+    pairs: impl Iterator<Item = Pair<'a, Rule>>,
+    options: &Options,
+) -> Result<Vec<InlineToken>, MarkdownError> {
+    let mut tokens = Vec::new();
+    for pair in pairs {
+        if pair.as_rule() == Rule::delimiter_run {
+            let span = pair.as_span();
+            let input = pair.get_input();
+            let before = input[..span.start()].chars().next_back();
+            let after = input[span.end()..].chars().next();
+            tokens.push(InlineToken::Delimiter(DelimiterRun::new(
+                pair.as_str(),
+                before,
+                after,
+            )));
+            continue;
         }
-        Rule::underline_formatting => {
-            let content = process_underline_content(pair)?;
-            Ok(format!("<u>{content}</u>"))
+
+        match (process_inline_element(pair, options)?, tokens.last_mut()) {
+            (Inline::Text(text), Some(InlineToken::Inline(Inline::Text(previous)))) => {
+                previous.push_str(&text)
+            }
+            (inline, _) => tokens.push(InlineToken::Inline(inline)),
         }
-        _ => Ok(html_escape::encode_text(pair.as_str()).to_string()),
     }
+
+    Ok(tokens)
 }
 
-fn process_bold_content(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
+/// Parse standalone text as inline content.
+fn parse_inlines(input: &str, options: &Options) -> Result<Vec<Inline>, MarkdownError> {
+    let mut parsed = MarkdownParser::parse(Rule::inline_text, input)
+        .map_err(|e| MarkdownError::ParseError(e.to_string()))?;
+    let text = parsed
         .next()
-        .map(|p| html_escape::encode_text(p.as_str()).to_string())
-        .ok_or_else(|| MarkdownError::ParseError("Empty bold content".to_string()))
+        .ok_or_else(|| MarkdownError::ParseError("Empty inline text".to_string()))?;
+
+    process_inlines(
+        text.into_inner().filter(|pair| pair.as_rule() != Rule::EOI),
+        options,
+    )
 }
 
-fn process_italic_content(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
-        .next()
-        .map(|p| html_escape::encode_text(p.as_str()).to_string())
-        .ok_or_else(|| MarkdownError::ParseError("Empty italic content".to_string()))
+/// Process inline elements (text, formatting, links, images).
+fn process_inline_element(pair: Pair<Rule>, options: &Options) -> Result<Inline, MarkdownError> {
+    match pair.as_rule() {
+        Rule::plain_text => Ok(Inline::Text(pair.as_str().to_string())),
+        Rule::inline_code => Ok(Inline::Code(process_code_span(pair.into_inner().as_str()))),
+        Rule::link => process_link(pair, options),
+        Rule::image => process_image(pair),
+        Rule::reference_link => process_reference_link(pair, options),
+        Rule::reference_image => process_reference_image(pair),
+        Rule::autolink => process_autolink(pair),
+        Rule::inline_html => Ok(Inline::Html(pair.as_str().to_string())),
+        Rule::hard_line_break => Ok(Inline::HardBreak),
+        Rule::entity_reference => Ok(Inline::Text(decode_entity(pair.as_str()))),
+        Rule::footnote_reference => Ok(Inline::FootnoteReference {
+            label: pair.into_inner().as_str().to_string(),
+        }),
+        Rule::escape_sequence => Ok(Inline::Text(process_escape_sequence(pair))),
+        _ => Ok(Inline::Text(pair.as_str().to_string())),
+    }
 }
 
-fn process_strikethrough_content(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
-        .next()
-        .map(|p| html_escape::encode_text(p.as_str()).to_string())
-        .ok_or_else(|| MarkdownError::ParseError("Empty strikethrough content".to_string()))
+/// Normalize code span content: line endings become spaces, and one space
+/// is stripped from both ends when present on both sides, so `` ` `` `` yields
+/// a lone backtick. Content made only of spaces is kept as is.
+fn process_code_span(content: &str) -> String {
+    let code = content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            match index {
+                0 => line,
+                _ => line.trim_start(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let stripped = code
+        .strip_prefix(' ')
+        .and_then(|code| code.strip_suffix(' '));
+    match stripped {
+        Some(stripped) if !code.trim_matches(' ').is_empty() => stripped.to_string(),
+        _ => code,
+    }
 }
 
-fn process_underline_content(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    pair.into_inner()
+/// Process markdown links [text](url "title").
+fn process_link(pair: Pair<Rule>, options: &Options) -> Result<Inline, MarkdownError> {
+    let mut children = None;
+    let mut url = String::new();
+    let mut title = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::link_content => children = Some(process_inlines(inner.into_inner(), options)?),
+            Rule::link_url => url = destination_text(inner.as_str()),
+            Rule::link_title => title = Some(title_text(inner.as_str())),
+            _ => {}
+        }
+    }
+
+    Ok(Inline::Link {
+        url,
+        title,
+        children: children
+            .ok_or_else(|| MarkdownError::ParseError("Missing link text".to_string()))?,
+    })
+}
+
+/// Process markdown images ![alt](url "title").
+fn process_image(pair: Pair<Rule>) -> Result<Inline, MarkdownError> {
+    let mut alt = None;
+    let mut url = String::new();
+    let mut title = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::image_alt => alt = Some(unescape_text(inner.as_str())),
+            Rule::image_url => url = destination_text(inner.as_str()),
+            Rule::link_title => title = Some(title_text(inner.as_str())),
+            _ => {}
+        }
+    }
+
+    Ok(Inline::Image {
+        url,
+        title,
+        alt: alt.ok_or_else(|| MarkdownError::ParseError("Missing image alt text".to_string()))?,
+    })
+}
+
+/// Process autolinks <https://example.com> and <user@example.com>.
+/// The link text is the literal destination; backslashes are not escapes.
+fn process_autolink(pair: Pair<Rule>) -> Result<Inline, MarkdownError> {
+    let target = pair
+        .into_inner()
         .next()
-        .map(|p| html_escape::encode_text(p.as_str()).to_string())
-        .ok_or_else(|| MarkdownError::ParseError("Empty underline content".to_string()))
+        .ok_or_else(|| MarkdownError::ParseError("Missing autolink target".to_string()))?;
+    let text = target.as_str().to_string();
+    let url = match target.as_rule() {
+        Rule::autolink_email => format!("mailto:{text}"),
+        _ => text.clone(),
+    };
+
+    Ok(Inline::Link {
+        url,
+        title: None,
+        children: vec![Inline::Text(text)],
+    })
 }
 
-/// Process markdown links [text](url).
-fn process_link(pair: Pair<Rule>) -> Result<String, MarkdownError> {
+/// Process reference links [text][label], [label][] and [label].
+fn process_reference_link(pair: Pair<Rule>, options: &Options) -> Result<Inline, MarkdownError> {
     let mut inner = pair.into_inner();
     let text = inner
         .next()
-        .map(|p| p.into_inner().as_str())
         .ok_or_else(|| MarkdownError::ParseError("Missing link text".to_string()))?;
-    let url = inner
-        .next()
-        .map(|p| p.as_str())
-        .ok_or_else(|| MarkdownError::ParseError("Missing link URL".to_string()))?;
+    let (label, kind) = reference_label(text.as_str(), inner.next());
 
-    Ok(format!(
-        "<a href=\"{}\">{}</a>",
-        url,
-        html_escape::encode_text(text)
-    ))
+    Ok(Inline::LinkReference {
+        label,
+        kind,
+        children: process_inlines(text.into_inner(), options)?,
+    })
 }
 
-/// Process markdown images ![alt](url).
-fn process_image(pair: Pair<Rule>) -> Result<String, MarkdownError> {
+/// Process reference images ![alt][label], ![alt][] and ![alt].
+fn process_reference_image(pair: Pair<Rule>) -> Result<Inline, MarkdownError> {
     let mut inner = pair.into_inner();
     let alt = inner
         .next()
-        .map(|p| p.into_inner().as_str())
         .ok_or_else(|| MarkdownError::ParseError("Missing image alt text".to_string()))?;
-    let url = inner
-        .next()
-        .map(|p| p.as_str())
-        .ok_or_else(|| MarkdownError::ParseError("Missing image URL".to_string()))?;
+    let (label, kind) = reference_label(alt.as_str(), inner.next());
 
-    Ok(format!(
-        "<img src=\"{}\" alt=\"{}\">",
-        url,
-        html_escape::encode_text(alt)
-    ))
+    Ok(Inline::ImageReference {
+        label,
+        kind,
+        alt: unescape_text(alt.as_str()),
+    })
+}
+
+/// Determine the label and form of a reference from its text and optional `[label]` suffix.
+fn reference_label(text: &str, suffix: Option<Pair<Rule>>) -> (String, ReferenceKind) {
+    match suffix.map(|suffix| suffix.into_inner().next()) {
+        Some(Some(label)) => (label.as_str().to_string(), ReferenceKind::Full),
+        Some(None) => (text.to_string(), ReferenceKind::Collapsed),
+        None => (text.to_string(), ReferenceKind::Shortcut),
+    }
+}
+
+/// Process link reference definitions [label]: url "title".
+fn process_link_definition(pair: Pair<Rule>) -> Result<Block, MarkdownError> {
+    let mut definition = LinkDefinition::default();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::link_label => definition.label = inner.as_str().to_string(),
+            Rule::link_destination => definition.url = destination_text(inner.as_str()),
+            Rule::link_title => definition.title = Some(title_text(inner.as_str())),
+            _ => {}
+        }
+    }
+
+    Ok(Block::Definition(definition))
 }
 
-fn process_document_quote(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    process_quote(pair)
+/// Link destination without its optional angle brackets, unescaped.
+fn destination_text(destination: &str) -> String {
+    let url = destination
+        .strip_prefix('<')
+        .and_then(|url| url.strip_suffix('>'))
+        .unwrap_or(destination);
+    unescape_text(url)
 }
 
-fn process_quote_line(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let inner = pair.into_inner().next();
-    match inner {
-        Some(content) => {
-            let html = convert_to_html(content)?;
-            Ok(format!("<p>{}</p>", html))
+/// Link title without its surrounding quotes or parentheses, unescaped.
+fn title_text(title: &str) -> String {
+    unescape_text(&title[1..title.len() - 1])
+}
+
+/// Process footnote definitions [^label]: text. Continuation lines are
+/// indented by four columns and may hold further paragraphs or other blocks.
+fn process_footnote_definition(
+    pair: Pair<Rule>,
+    options: &Options,
+) -> Result<Block, MarkdownError> {
+    let mut label = String::new();
+    let mut content = String::new();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::footnote_label => label = inner.as_str().to_string(),
+            Rule::list_item_text => {
+                content.push_str(inner.as_str());
+                content.push('\n');
+            }
+            Rule::list_item_children => {
+                for line in inner.as_str().lines() {
+                    content.push_str(&strip_continuation(line, 4));
+                    content.push('\n');
+                }
+            }
+            _ => {}
         }
-        None => Ok("<p></p>".to_string()),
     }
+
+    Ok(Block::FootnoteDefinition(FootnoteDefinition {
+        label,
+        children: parse_blocks(&content, options)?,
+    }))
+}
+
+/// Resolve backslash escapes of ASCII punctuation and entity references
+/// in raw text such as link destinations and titles.
+fn unescape_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        let consumed = if ch == '\\' && rest[1..].starts_with(|c: char| c.is_ascii_punctuation()) {
+            result.push_str(&rest[1..2]);
+            2
+        } else if ch == '&'
+            && let Some(reference) = entity_reference(rest)
+        {
+            result.push_str(&decode_entity(reference));
+            reference.len()
+        } else {
+            result.push(ch);
+            ch.len_utf8()
+        };
+        rest = &rest[consumed..];
+    }
+
+    result
+}
+
+/// Entity or numeric character reference at the start of `text`.
+fn entity_reference(text: &str) -> Option<&str> {
+    MarkdownParser::parse(Rule::entity_reference, text)
+        .ok()
+        .and_then(|mut pairs| pairs.next())
+        .map(|pair| &text[..pair.as_str().len()])
 }
 
-fn process_quote(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let mut lines: Vec<String> = Vec::new();
+/// Decode an entity (`&copy;`) or numeric character reference (`&#169;`,
+/// `&#xA9;`). Unknown entity names stay literal; invalid code points and
+/// `&#0;` become U+FFFD.
+fn decode_entity(reference: &str) -> String {
+    let name = &reference[1..reference.len() - 1];
+    let code_point = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X"))
+    {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(decimal) = name.strip_prefix('#') {
+        decimal.parse().ok()
+    } else {
+        return html_escape::decode_html_entities(reference).into_owned();
+    };
+
+    code_point
+        .filter(|&code| code != 0)
+        .and_then(char::from_u32)
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+        .to_string()
+}
+
+/// Process a blockquote. The text after each `>` marker and the lazy
+/// continuation lines are parsed as blocks, so quotes hold paragraphs,
+/// lists, code, headings and nested quotes. A quote starting with an
+/// alert marker such as `[!NOTE]` becomes an alert.
+fn process_quote(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    let mut alert = None;
+    let mut content = String::new();
 
     for line in pair.into_inner() {
-        let processed = process_quote_line(line)?;
-        if !processed.is_empty() {
-            lines.push(processed);
+        if line.as_rule() == Rule::alert_marker {
+            alert = Some(alert_heading(line));
+            continue;
+        }
+        let mut texts = line.into_inner().peekable();
+        if texts.peek().is_none() {
+            content.push('\n');
+        }
+        for text in texts {
+            let line_text = text.as_str().trim_end_matches(['\r', '\n']);
+            match text.as_rule() {
+                Rule::quote_lazy_line => content.push_str(&escape_lazy_line(line_text)),
+                _ => content.push_str(line_text),
+            }
+            content.push('\n');
+        }
+    }
+
+    let children = parse_blocks(&content, options)?;
+    Ok(match alert {
+        Some((kind, title)) => Block::Alert {
+            kind,
+            title,
+            children,
+        },
+        None => Block::BlockQuote { children },
+    })
+}
+
+/// Process Docusaurus-style admonitions :::note Title ... :::.
+fn process_admonition(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    let mut content = "";
+    for inner in pair.clone().into_inner() {
+        if inner.as_rule() == Rule::admonition_body {
+            content = inner.as_str();
+        }
+    }
+    let (kind, title) = alert_heading(pair);
+
+    Ok(Block::Alert {
+        kind,
+        title,
+        children: parse_blocks(content, options)?,
+    })
+}
+
+/// Kind and optional custom title of an alert marker or admonition fence.
+fn alert_heading(pair: Pair<Rule>) -> (AlertKind, Option<String>) {
+    let mut kind = AlertKind::Note;
+    let mut title = None;
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::alert_kind | Rule::admonition_kind => {
+                kind = match inner.as_str().to_ascii_lowercase().as_str() {
+                    "tip" => AlertKind::Tip,
+                    "important" => AlertKind::Important,
+                    "warning" => AlertKind::Warning,
+                    "caution" | "danger" => AlertKind::Caution,
+                    _ => AlertKind::Note,
+                };
+            }
+            Rule::alert_title => title = Some(inner.as_str().to_string()),
+            _ => {}
+        }
+    }
+
+    (kind, title)
+}
+
+/// Process a list. The list is loose when blank lines separate its items
+/// or the blocks inside any item, and tight otherwise.
+fn process_list(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    let kind = list_kind(pair.as_str());
+    let mut items = Vec::new();
+    let mut loose = false;
+    let mut previous_end = None;
+
+    for item in pair.into_inner() {
+        let span = item.as_span();
+        if let Some(end) = previous_end {
+            loose |= span.start() > end;
+        }
+        previous_end = Some(span.end());
+
+        let (item, separated) = process_list_item(item, options)?;
+        loose |= separated;
+        items.push(item);
+    }
+
+    Ok(Block::List {
+        kind,
+        tight: !loose,
+        items,
+    })
+}
+
+/// Determine the list kind from the marker of its first item.
+fn list_kind(list: &str) -> ListKind {
+    let marker = list.trim_start_matches(' ');
+    let digits = marker.len()
+        - marker
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+
+    if digits == 0 {
+        ListKind::Bullet(marker.chars().next().unwrap_or('-'))
+    } else {
+        ListKind::Ordered {
+            start: marker[..digits].parse().unwrap_or(1),
+            delimiter: marker[digits..].chars().next().unwrap_or('.'),
+        }
+    }
+}
+
+/// Process a list item together with its continuation lines.
+/// The item text is dedented to the item's content column and parsed as blocks,
+/// so nested lists, paragraphs, code blocks and quotes become children of the item.
+/// Also reports whether blank lines separate the item's blocks.
+fn process_list_item(
+    pair: Pair<Rule>,
+    options: &Options,
+) -> Result<(ListItem, bool), MarkdownError> {
+    let item_start = pair.as_span().start();
+    // Content starts after the marker and the whitespace following it
+    let content_start = pair
+        .as_str()
+        .trim_start_matches(' ')
+        .trim_start_matches(|c: char| !c.is_whitespace())
+        .trim_start_matches([' ', '\t']);
+    let mut content_indent = pair.as_str().len() - content_start.len();
+    let mut checked = None;
+    let mut content = String::new();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::task_marker => {
+                // Continuation lines align with the task marker, not its text
+                content_indent = inner.as_span().start() - item_start;
+                checked = Some(!inner.as_str().starts_with("[ ]"));
+            }
+            Rule::list_item_text => {
+                if checked.is_none() {
+                    content_indent = inner.as_span().start() - item_start;
+                }
+                content.push_str(inner.as_str());
+                content.push('\n');
+            }
+            Rule::list_item_children => {
+                for line in inner.as_str().lines() {
+                    content.push_str(&strip_continuation(line, content_indent));
+                    content.push('\n');
+                }
+            }
+            _ => {}
         }
     }
 
-    Ok(format!("<blockquote>\n{}\n</blockquote>", lines.join("\n")))
+    let (children, separated) = parse_blocks_with_gaps(&content, options)?;
+    Ok((ListItem { checked, children }, separated))
 }
 
-fn process_code_fence(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    process_code_block(pair)
+/// Remove `width` columns of indentation from a line continuing a list item
+/// or footnote. Lazy lines, indented less than that, are kept as they are
+/// so they stay paragraph text.
+fn strip_continuation(line: &str, width: usize) -> Cow<'_, str> {
+    let content = line.trim_start_matches([' ', '\t']);
+    let columns = line[..line.len() - content.len()]
+        .chars()
+        .fold(0, |column, ch| match ch {
+            '\t' => column + 4 - column % 4,
+            _ => column + 1,
+        });
+
+    if columns < width && !content.is_empty() {
+        escape_lazy_line(line)
+    } else {
+        Cow::Borrowed(strip_indent(line, width))
+    }
 }
 
-fn process_unordered_list(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let items: Result<Vec<String>, MarkdownError> =
-        pair.into_inner().map(process_list_item).collect();
+/// Escape a lazy continuation line that reads as a setext underline, such as
+/// `===`, so it stays text of the paragraph it continues.
+fn escape_lazy_line(line: &str) -> Cow<'_, str> {
+    let content = line.trim_start_matches([' ', '\t']);
+    let underline = content.trim_end();
+    if !underline.is_empty()
+        && (underline.bytes().all(|b| b == b'=') || underline.bytes().all(|b| b == b'-'))
+    {
+        let indent = &line[..line.len() - content.len()];
+        Cow::Owned(format!("{indent}\\{content}"))
+    } else {
+        Cow::Borrowed(line)
+    }
+}
 
-    Ok(format!("<ul>\n{}\n</ul>", items?.join("\n")))
+/// Remove up to `width` columns of leading indentation from a line.
+/// Tabs advance to the next multiple of four columns.
+fn strip_indent(line: &str, width: usize) -> &str {
+    let mut column = 0;
+    for (index, ch) in line.char_indices() {
+        if column >= width {
+            return &line[index..];
+        }
+        match ch {
+            ' ' => column += 1,
+            '\t' => column += 4 - column % 4,
+            _ => return &line[index..],
+        }
+    }
+
+    ""
 }
 
-fn process_ordered_list(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let items: Result<Vec<String>, MarkdownError> =
-        pair.into_inner().map(process_list_item).collect();
+/// Process GitHub Flavored Markdown pipe tables.
+/// Body rows are padded or truncated to the header width.
+fn process_table(pair: Pair<Rule>, options: &Options) -> Result<Block, MarkdownError> {
+    let mut alignments = Vec::new();
+    let mut header = Vec::new();
+    let mut rows = Vec::new();
+
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::table_header => header = process_table_cells(inner, options)?,
+            Rule::table_delimiter_row => {
+                alignments = inner.into_inner().map(table_alignment).collect();
+            }
+            Rule::table_row => {
+                let mut cells = process_table_cells(inner, options)?;
+                cells.resize_with(alignments.len(), TableCell::default);
+                rows.push(cells);
+            }
+            _ => {}
+        }
+    }
 
-    Ok(format!("<ol>\n{}\n</ol>", items?.join("\n")))
+    Ok(Block::Table {
+        alignments,
+        header,
+        rows,
+    })
 }
 
-fn process_list_item(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let content = pair.as_str();
-    let text = content
-        .find(char::is_whitespace)
-        .map(|pos| &content[pos + 1..])
-        .unwrap_or("")
-        .trim_end_matches('\n')
-        .trim();
+fn process_table_cells(
+    pair: Pair<Rule>,
+    options: &Options,
+) -> Result<Vec<TableCell>, MarkdownError> {
+    pair.into_inner()
+        .map(|cell| {
+            let text = cell.as_str().trim().replace("\\|", "|");
+            Ok(TableCell {
+                children: parse_inlines(&text, options)?,
+            })
+        })
+        .collect()
+}
 
-    Ok(format!("<li>{}</li>", html_escape::encode_text(text)))
+fn table_alignment(pair: Pair<Rule>) -> Alignment {
+    let spec = pair.as_str().trim();
+    match (spec.starts_with(':'), spec.ends_with(':')) {
+        (true, true) => Alignment::Center,
+        (true, false) => Alignment::Left,
+        (false, true) => Alignment::Right,
+        (false, false) => Alignment::None,
+    }
 }
 
-/// Process fenced code blocks with optional language specification.
-/// Supports syntax like ```rust\ncode here\n```
-fn process_code_block(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let mut language = String::new();
-    let mut code = String::new();
+/// Process fenced code blocks with optional info string.
+/// Supports syntax like ```rust\ncode here\n``` and ~~~ fences. Content lines
+/// lose as much indentation as the opening fence has.
+fn process_code_block(pair: Pair<Rule>) -> Result<Block, MarkdownError> {
+    let fence_indent = pair.as_str().len() - pair.as_str().trim_start_matches(' ').len();
+    let mut info = String::new();
+    let mut literal = String::new();
 
     for inner_pair in pair.into_inner() {
         match inner_pair.as_rule() {
             Rule::language_spec => {
-                language = inner_pair.as_str().trim().to_string();
+                info = inner_pair.as_str().to_string();
             }
             Rule::code_body => {
-                code = html_escape::encode_text(inner_pair.as_str()).to_string();
+                let lines: Vec<&str> = inner_pair
+                    .as_str()
+                    .split('\n')
+                    .map(|line| strip_indent(line.trim_end_matches('\r'), fence_indent))
+                    .collect();
+                literal = lines.join("\n");
             }
             _ => {} // Skip other elements like whitespace, newlines, fences
         }
     }
 
-    let lang_attr = if language.is_empty() {
-        String::new()
-    } else {
-        format!(" class=\"language-{}\"", language)
-    };
+    Ok(Block::CodeBlock { info, literal })
+}
+
+/// Process indented code blocks, removing four columns of indentation per line.
+fn process_indented_code(pair: Pair<Rule>) -> Result<Block, MarkdownError> {
+    let literal: Vec<&str> = pair
+        .as_str()
+        .lines()
+        .map(|line| strip_indent(line, 4))
+        .collect();
 
-    Ok(format!("<pre><code{lang_attr}>{code}</code></pre>"))
+    Ok(Block::CodeBlock {
+        info: String::new(),
+        literal: literal.join("\n"),
+    })
 }
 
-fn process_escape_sequence(pair: Pair<Rule>) -> Result<String, MarkdownError> {
-    let escaped = pair.into_inner().next().map(|p| p.as_str()).unwrap_or("");
-    Ok(html_escape::encode_text(escaped).to_string())
+fn process_escape_sequence(pair: Pair<Rule>) -> String {
+    pair.into_inner()
+        .next()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_default()
 }
 
 /// Convert markdown file to HTML file.
@@ -400,6 +1069,23 @@ fn process_escape_sequence(pair: Pair<Rule>) -> Result<String, MarkdownError> {
 /// # Returns
 /// Ok(()) on success or MarkdownError
 pub fn convert_file_to_html(input_path: &Path, output_path: &Path) -> Result<(), MarkdownError> {
+    convert_file_to_html_with_options(input_path, output_path, &Options::default())
+}
+
+/// Convert markdown file to HTML file with optional extensions enabled.
+///
+/// # Arguments
+/// * `input_path` - Path to markdown file
+/// * `output_path` - Path where HTML will be written
+/// * `options` - Extensions to enable
+///
+/// # Returns
+/// Ok(()) on success or MarkdownError
+pub fn convert_file_to_html_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    options: &Options,
+) -> Result<(), MarkdownError> {
     let file = File::open(input_path)?;
     let reader = BufReader::new(file);
 
@@ -409,7 +1095,7 @@ pub fn convert_file_to_html(input_path: &Path, output_path: &Path) -> Result<(),
         content.push('\n');
     }
 
-    let html_lines = str_to_html(&content)?;
+    let html_lines = str_to_html_with_options(&content, options)?;
 
     let mut output = OpenOptions::new()
         .create(true)
@@ -433,7 +1119,22 @@ pub fn convert_file_to_html(input_path: &Path, output_path: &Path) -> Result<(),
 /// # Returns
 /// Ok(()) on success or MarkdownError
 pub fn print_html_to_console(input: &str) -> Result<(), MarkdownError> {
-    let html_lines = str_to_html(input)?;
+    print_html_to_console_with_options(input, &Options::default())
+}
+
+/// Print HTML conversion result to console with optional extensions enabled.
+///
+/// # Arguments
+/// * `input` - Markdown text to convert and print
+/// * `options` - Extensions to enable
+///
+/// # Returns
+/// Ok(()) on success or MarkdownError
+pub fn print_html_to_console_with_options(
+    input: &str,
+    options: &Options,
+) -> Result<(), MarkdownError> {
+    let html_lines = str_to_html_with_options(input, options)?;
     for line in html_lines {
         println!("{}", line);
     }
@@ -1,5 +1,7 @@
-use arinamcnulty_markdown_parser::{MarkdownError, convert_file_to_html, print_html_to_console};
-use clap::{Arg, ArgMatches, Command};
+use arinamcnulty_markdown_parser::{
+    MarkdownError, Options, convert_file_to_html_with_options, print_html_to_console_with_options,
+};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use std::path::PathBuf;
 
 fn main() -> Result<(), MarkdownError> {
@@ -51,6 +53,7 @@ fn build_cli_app() -> Command {
                         .required(true)
                         .value_parser(clap::value_parser!(PathBuf))
                 )
+                .args(option_args())
         )
         .subcommand(
             Command::new("parse")
@@ -77,6 +80,7 @@ fn build_cli_app() -> Command {
                         .help("Path to Markdown file to parse and convert")
                         .value_parser(clap::value_parser!(PathBuf))
                 )
+                .args(option_args())
         )
         .subcommand(
             Command::new("info")
@@ -89,11 +93,47 @@ fn build_cli_app() -> Command {
         .arg_required_else_help(true)
 }
 
+/// Flags enabling optional syntax extensions, shared by `convert` and `parse`.
+fn option_args() -> Vec<Arg> {
+    vec![
+        Arg::new("extended-autolinks")
+            .long("extended-autolinks")
+            .help("Turn bare URLs and email addresses into links")
+            .action(ArgAction::SetTrue),
+        Arg::new("unsafe-html")
+            .long("unsafe-html")
+            .help("Pass raw HTML through instead of escaping it")
+            .action(ArgAction::SetTrue),
+        Arg::new("hard-breaks")
+            .long("hard-breaks")
+            .help("Render every line break inside a paragraph as <br>")
+            .action(ArgAction::SetTrue),
+        Arg::new("underline")
+            .long("underline")
+            .help("Render __text__ as underline instead of bold")
+            .action(ArgAction::SetTrue),
+        Arg::new("heading-anchors")
+            .long("heading-anchors")
+            .help("Add a permalink anchor to every heading")
+            .action(ArgAction::SetTrue),
+    ]
+}
+
+fn parse_options(matches: &ArgMatches) -> Options {
+    Options {
+        extended_autolinks: matches.get_flag("extended-autolinks"),
+        unsafe_html: matches.get_flag("unsafe-html"),
+        hard_breaks: matches.get_flag("hard-breaks"),
+        underline: matches.get_flag("underline"),
+        heading_anchors: matches.get_flag("heading-anchors"),
+    }
+}
+
 fn handle_convert_command(matches: &ArgMatches) -> Result<(), MarkdownError> {
     let input_path: &PathBuf = matches.get_one("input").expect("Input path is required");
     let output_path: &PathBuf = matches.get_one("output").expect("Output path is required");
 
-    match convert_file_to_html(input_path, output_path) {
+    match convert_file_to_html_with_options(input_path, output_path, &parse_options(matches)) {
         Ok(()) => {
             println!("HTML file saved to: {}", output_path.display());
             Ok(())
@@ -115,7 +155,7 @@ fn handle_parse_command(matches: &ArgMatches) -> Result<(), MarkdownError> {
             .clone()
     };
 
-    print_html_to_console(&markdown_text)
+    print_html_to_console_with_options(&markdown_text, &parse_options(matches))
 }
 
 fn handle_info_command() -> Result<(), MarkdownError> {
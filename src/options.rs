@@ -0,0 +1,23 @@
+//! Options controlling parsing and rendering.
+
+/// Optional extensions to the default syntax and rendering.
+/// `Options::default()` gives the behaviour of
+/// [`parse_markdown`](crate::parse_markdown), [`render_html`](crate::render_html)
+/// and [`str_to_html`](crate::str_to_html).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Turn bare `www.` and `http(s)://` URLs and email addresses in text
+    /// into links (GitHub Flavored Markdown extended autolinks).
+    pub extended_autolinks: bool,
+    /// Pass raw HTML blocks and inline HTML through verbatim. When disabled
+    /// raw HTML is escaped and shows as literal text.
+    pub unsafe_html: bool,
+    /// Render every soft line break inside a paragraph as `<br>`, as chat
+    /// style content expects.
+    pub hard_breaks: bool,
+    /// Parse `__text__` as underline instead of strong emphasis.
+    pub underline: bool,
+    /// Emit an `<a class="anchor">` permalink at the start of each heading
+    /// that has an id.
+    pub heading_anchors: bool,
+}
@@ -3,6 +3,7 @@ use arinamcnulty_markdown_parser::MarkdownParser;
 use arinamcnulty_markdown_parser::*;
 use pest::Parser;
 
+#[allow(clippy::result_large_err)]
 fn parse_by_rule(
     rule: Rule,
     input: &str,
@@ -245,28 +246,29 @@ mod tests {
 
     #[test]
     fn check_italic() -> Result<()> {
-        let input = "*this text is italic*";
-        let mut pairs = parse_by_rule(Rule::italic_formatting, input)?;
-        let pair = get_single_pair(&mut pairs, Rule::italic_formatting, "asterisk italic")?;
-        let content = get_inner_pair(&pair, Rule::italic_content, "italic content")?;
-        assert_eq!(content.as_str(), "this text is italic");
+        for input in ["*this text is italic*", "_this text is italic_"] {
+            let document = parse_markdown(input)?;
+            assert_eq!(
+                document.blocks,
+                vec![Block::Paragraph {
+                    children: vec![Inline::Emphasis(vec![Inline::Text(
+                        "this text is italic".to_string()
+                    )])]
+                }]
+            );
+        }
 
-        let input = "_italic test 2_";
-        let mut pairs = parse_by_rule(Rule::italic_formatting, input)?;
-        let pair = get_single_pair(&mut pairs, Rule::italic_formatting, "underscore italic")?;
-        let content = get_inner_pair(&pair, Rule::italic_content, "italic content")?;
-        assert_eq!(content.as_str(), "italic test 2");
+        let mut pairs = parse_by_rule(Rule::delimiter_run, "**text")?;
+        let pair = get_single_pair(&mut pairs, Rule::delimiter_run, "delimiter run")?;
+        assert_eq!(pair.as_str(), "**");
 
         std::result::Result::Ok(())
     }
 
     #[test]
     fn check_bold() -> Result<()> {
-        let input = "**this text is bold**";
-        let mut pairs = parse_by_rule(Rule::bold_formatting, input)?;
-        let pair = get_single_pair(&mut pairs, Rule::bold_formatting, "bold text")?;
-        let content = get_inner_pair(&pair, Rule::bold_content, "bold content")?;
-        assert_eq!(content.as_str(), "this text is bold");
+        let html = str_to_html("**this text is bold**")?;
+        assert_eq!(html, vec!["<p><strong>this text is bold</strong></p>"]);
 
         std::result::Result::Ok(())
     }
@@ -274,25 +276,68 @@ mod tests {
     #[test]
     fn check_underline() -> Result<()> {
         let input = "__underlined text!__";
-        let mut pairs = parse_by_rule(Rule::underline_formatting, input)?;
-        let pair = get_single_pair(&mut pairs, Rule::underline_formatting, "underline text")?;
-        let content = get_inner_pair(&pair, Rule::underline_content, "underline content")?;
-        assert_eq!(content.as_str(), "underlined text!");
+        let html = str_to_html(input)?;
+        assert_eq!(html, vec!["<p><strong>underlined text!</strong></p>"]);
+
+        let options = Options {
+            underline: true,
+            ..Options::default()
+        };
+        let document = parse_markdown_with_options(input, &options)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Paragraph {
+                children: vec![Inline::Underline(vec![Inline::Text(
+                    "underlined text!".to_string()
+                )])]
+            }]
+        );
+        assert_eq!(
+            str_to_html_with_options("__u__ **b**", &options)?,
+            vec!["<p><u>u</u> <strong>b</strong></p>"]
+        );
 
         std::result::Result::Ok(())
     }
 
     #[test]
     fn check_strikethrough() -> Result<()> {
-        let input = "~~some striked text!~~";
-        let mut pairs = parse_by_rule(Rule::strikethrough_formatting, input)?;
-        let pair = get_single_pair(
-            &mut pairs,
-            Rule::strikethrough_formatting,
-            "strikethrough text",
-        )?;
-        let content = get_inner_pair(&pair, Rule::strikethrough_content, "strikethrough content")?;
-        assert_eq!(content.as_str(), "some striked text!");
+        let html = str_to_html("~~some striked text!~~ and ~one~")?;
+        assert_eq!(
+            html,
+            vec!["<p><del>some striked text!</del> and <del>one</del></p>"]
+        );
+
+        let html = str_to_html("a ~~~three~~~ and ~~mismatched~")?;
+        assert_eq!(html, vec!["<p>a ~~~three~~~ and ~~mismatched~</p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_emphasis_delimiter_runs() -> Result<()> {
+        let cases = [
+            ("snake_case_names", "<p>snake_case_names</p>"),
+            ("2*3*4", "<p>2<em>3</em>4</p>"),
+            ("***both***", "<p><em><strong>both</strong></em></p>"),
+            ("__x__", "<p><strong>x</strong></p>"),
+            (
+                "*foo**bar**baz*",
+                "<p><em>foo<strong>bar</strong>baz</em></p>",
+            ),
+            ("*foo**bar*", "<p><em>foo**bar</em></p>"),
+            ("**foo*", "<p>*<em>foo</em></p>"),
+            ("foo*bar*", "<p>foo<em>bar</em></p>"),
+            ("foo_bar_", "<p>foo_bar_</p>"),
+            ("a * b * c", "<p>a * b * c</p>"),
+            ("*(*foo*)*", "<p><em>(<em>foo</em>)</em></p>"),
+            ("*foo\nbar*", "<p><em>foo\nbar</em></p>"),
+            ("*a `*` b*", "<p><em>a <code>*</code> b</em></p>"),
+            ("*[link*](x)", "<p>*<a href=\"x\">link*</a></p>"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(str_to_html(input)?, vec![expected], "input: {input:?}");
+        }
 
         std::result::Result::Ok(())
     }
@@ -310,6 +355,19 @@ mod tests {
         let url = get_single_pair(&mut inner_iter, Rule::link_url, "link url")?;
         assert_eq!(url.as_str(), "https://google.com/");
 
+        // Balanced parentheses nest up to three levels in a destination
+        let html = str_to_html("[a](x(b(c(d)))) [a](x(b(c(d(e)))))")?;
+        assert_eq!(
+            html,
+            vec!["<p><a href=\"x(b(c(d)))\">a</a> [a](x(b(c(d(e)))))</p>"]
+        );
+
+        // Deep or unclosed parentheses stay text without deep recursion
+        let input = format!("[a]({}", "(".repeat(20_000));
+        assert_eq!(str_to_html(&input)?, vec![format!("<p>{input}</p>")]);
+        let input = "[a](".repeat(4000);
+        assert_eq!(str_to_html(&input)?, vec![format!("<p>{input}</p>")]);
+
         std::result::Result::Ok(())
     }
 
@@ -332,72 +390,249 @@ mod tests {
     #[test]
     fn check_quote() -> Result<()> {
         let input =
-            ">This is a text in a quote\n>this is also a part of a quote\n>this one is too.";
+            ">This is a text in a quote\n>this is also a part of a quote\nthis one is lazy.";
         let mut pairs = parse_by_rule(Rule::document_quote, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_quote, "blockquote")?;
 
-        let pair = pairs
-            .next()
-            .ok_or_else(|| anyhow!("Expected a quote, but found none"))?;
+        let mut quote_inner = pair.into_inner();
+        let quote_line1 = get_single_pair(&mut quote_inner, Rule::quote_line, "first line")?;
+        let text1 = get_inner_pair(&quote_line1, Rule::quote_text, "first line text")?;
+        assert_eq!(text1.as_str(), "This is a text in a quote");
+
+        let quote_line2 = get_single_pair(&mut quote_inner, Rule::quote_line, "second line")?;
+        let mut line2_inner = quote_line2.into_inner();
+        let text2 = get_single_pair(&mut line2_inner, Rule::quote_text, "second line text")?;
+        assert_eq!(text2.as_str(), "this is also a part of a quote");
+        let lazy = get_single_pair(&mut line2_inner, Rule::quote_lazy_line, "lazy line")?;
+        assert_eq!(lazy.as_str(), "this one is lazy.");
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote>\n<p>This is a text in a quote\nthis is also a part of a quote\nthis one is lazy.</p>\n</blockquote>"
+            ]
+        );
 
-        assert_eq!(pair.as_rule(), Rule::document_quote);
+        std::result::Result::Ok(())
+    }
 
-        let mut quote_inner = pair.into_inner();
-        let quote_line1 = quote_inner
-            .next()
-            .ok_or_else(|| anyhow!("Expected a quote_line, but found none"))?;
+    #[test]
+    fn check_nested_blockquotes() -> Result<()> {
+        let document = parse_markdown("> > nested\n> > lazy\n>\n> # Title\n> - item\n")?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::BlockQuote {
+                children: vec![
+                    Block::BlockQuote {
+                        children: vec![Block::Paragraph {
+                            children: vec![
+                                Inline::Text("nested".to_string()),
+                                Inline::SoftBreak,
+                                Inline::Text("lazy".to_string()),
+                            ]
+                        }]
+                    },
+                    Block::Heading {
+                        level: 1,
+                        id: None,
+                        children: vec![Inline::Text("Title".to_string())]
+                    },
+                    Block::List {
+                        kind: ListKind::Bullet('-'),
+                        tight: true,
+                        items: vec![ListItem {
+                            checked: None,
+                            children: vec![Block::Paragraph {
+                                children: vec![Inline::Text("item".to_string())]
+                            }]
+                        }]
+                    },
+                ]
+            }]
+        );
 
-        assert_eq!(quote_line1.as_rule(), Rule::quote_line);
+        let html = str_to_html("> ```rust\n> let x = 1;\n> ```\nafter\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote>\n<pre><code class=\"language-rust\">let x = 1;</code></pre>\n</blockquote>",
+                "<p>after</p>"
+            ]
+        );
 
-        let mut quote_line1_inner = quote_line1.into_inner();
-        let paragraph_text1 = quote_line1_inner.next().ok_or_else(|| {
-            anyhow!("Expected paragraph_text in the first quote line, but found none")
-        })?;
+        let html = str_to_html("> first\n>\n> second\n\n> other quote")?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote>\n<p>first</p>\n<p>second</p>\n</blockquote>",
+                "<blockquote>\n<p>other quote</p>\n</blockquote>"
+            ]
+        );
 
-        assert_eq!(paragraph_text1.as_rule(), Rule::paragraph_text);
-        let plain_text1 = paragraph_text1
-            .into_inner()
-            .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the first line, but found none"))?;
+        let html = str_to_html(">\n> text")?;
+        assert_eq!(html, vec!["<blockquote>\n<p>text</p>\n</blockquote>"]);
 
-        assert_eq!(plain_text1.as_str(), "This is a text in a quote");
+        std::result::Result::Ok(())
+    }
 
-        let quote_line2 = quote_inner
-            .next()
-            .ok_or_else(|| anyhow!("Expected the second quote_line, but found none"))?;
+    #[test]
+    fn check_lazy_continuation() -> Result<()> {
+        // A lazy line continues a paragraph, never underlines it
+        let html = str_to_html("> foo\n===")?;
+        assert_eq!(html, vec!["<blockquote>\n<p>foo\n===</p>\n</blockquote>"]);
+        let html = str_to_html("- foo\n===")?;
+        assert_eq!(html, vec!["<ul>\n<li>foo\n===</li>\n</ul>"]);
+
+        // Code blocks take no lazy lines
+        let html = str_to_html(">     code\nlazy")?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote>\n<pre><code>code</code></pre>\n</blockquote>",
+                "<p>lazy</p>"
+            ]
+        );
+        let html = str_to_html("> ```\n> x\ny\n> ```")?;
+        assert_eq!(
+            html,
+            vec![
+                "<blockquote>\n<pre><code>x\n</code></pre>\n</blockquote>",
+                "<p>y</p>",
+                "<blockquote>\n<pre><code></code></pre>\n</blockquote>"
+            ]
+        );
+        let html = str_to_html("- ```\n  a\nb\n  ```")?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>\n<pre><code>a\n</code></pre>\n</li>\n</ul>",
+                "<p>b</p>",
+                "<pre><code></code></pre>"
+            ]
+        );
 
-        assert_eq!(quote_line2.as_rule(), Rule::quote_line);
+        // Nor do headings
+        let html = str_to_html("- # h\nlazy")?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>\n<h1 id=\"h\">h</h1>\n</li>\n</ul>",
+                "<p>lazy</p>"
+            ]
+        );
 
-        let mut quote_line2_inner = quote_line2.into_inner();
-        let paragraph_text2 = quote_line2_inner.next().ok_or_else(|| {
-            anyhow!("Expected paragraph_text in the second quote line, but found none")
-        })?;
+        // Indented lines after a paragraph continue it
+        let html = str_to_html("> a\n>     b\nc")?;
+        assert_eq!(html, vec!["<blockquote>\n<p>a\nb\nc</p>\n</blockquote>"]);
 
-        assert_eq!(paragraph_text2.as_rule(), Rule::paragraph_text);
-        let plain_text2 = paragraph_text2
-            .into_inner()
-            .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the second line, but found none"))?;
+        std::result::Result::Ok(())
+    }
 
-        assert_eq!(plain_text2.as_str(), "this is also a part of a quote");
+    #[test]
+    fn check_alerts() -> Result<()> {
+        let input = "> [!WARNING]\n> Back up *first*.";
+        let mut pairs = parse_by_rule(Rule::document_quote, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_quote, "alert")?;
+        let marker = get_inner_pair(&pair, Rule::alert_marker, "alert marker")?;
+        let kind = get_inner_pair(&marker, Rule::alert_kind, "alert kind")?;
+        assert_eq!(kind.as_str(), "WARNING");
+
+        let document = parse_markdown(input)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Alert {
+                kind: AlertKind::Warning,
+                title: None,
+                children: vec![Block::Paragraph {
+                    children: vec![
+                        Inline::Text("Back up ".to_string()),
+                        Inline::Emphasis(vec![Inline::Text("first".to_string())]),
+                        Inline::Text(".".to_string()),
+                    ]
+                }]
+            }]
+        );
+        assert_eq!(
+            render_html(&document),
+            vec![
+                "<div class=\"markdown-alert markdown-alert-warning\">\n<p class=\"markdown-alert-title\">Warning</p>\n<p>Back up <em>first</em>.</p>\n</div>"
+            ]
+        );
 
-        let quote_line3 = quote_inner
-            .next()
-            .ok_or_else(|| anyhow!("Expected the third quote_line, but found none"))?;
+        let html = str_to_html(":::tip Keyboard shortcuts\nPress **K**.\n:::\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<div class=\"markdown-alert markdown-alert-tip\">\n<p class=\"markdown-alert-title\">Keyboard shortcuts</p>\n<p>Press <strong>K</strong>.</p>\n</div>"
+            ]
+        );
 
-        assert_eq!(quote_line3.as_rule(), Rule::quote_line);
+        let body_after_blank = vec![
+            "<div class=\"markdown-alert markdown-alert-note\">\n<p class=\"markdown-alert-title\">Note</p>\n<p>Body</p>\n</div>",
+        ];
+        assert_eq!(str_to_html("> [!NOTE]\n>\n> Body")?, body_after_blank);
+        assert_eq!(str_to_html(":::note\n\nBody\n:::")?, body_after_blank);
 
-        let mut quote_line3_inner = quote_line3.into_inner();
-        let paragraph_text3 = quote_line3_inner.next().ok_or_else(|| {
-            anyhow!("Expected paragraph_text in the third quote line, but found none")
-        })?;
+        let html = str_to_html("> [!UNKNOWN]\n> text")?;
+        assert_eq!(
+            html,
+            vec!["<blockquote>\n<p>[!UNKNOWN]\ntext</p>\n</blockquote>"]
+        );
 
-        assert_eq!(paragraph_text3.as_rule(), Rule::paragraph_text);
-        let plain_text3 = paragraph_text3
-            .into_inner()
-            .next()
-            .ok_or_else(|| anyhow!("Expected plain_text in the third line, but found none"))?;
+        std::result::Result::Ok(())
+    }
 
-        assert_eq!(plain_text3.as_str(), "this one is too.");
+    #[test]
+    fn check_front_matter() -> Result<()> {
+        let input = "---\ntitle: \"Hello: world\" # comment\ndate: 2024-05-01\ntags: [news, 'rust, lang']\nauthors:\n  - Ann\n  - Bob\n---\n# Body\n";
+        let mut pairs = parse_by_rule(Rule::front_matter, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::front_matter, "front matter")?;
+        let body = get_inner_pair(&pair, Rule::front_matter_body, "front matter body")?;
+        assert!(body.as_str().starts_with("title:"));
+
+        let (front_matter, html) = str_to_html_with_front_matter(input, &Options::default())?;
+        assert_eq!(html, vec!["<h1 id=\"body\">Body</h1>"]);
+        let front_matter = front_matter.ok_or_else(|| anyhow!("Expected front matter"))?;
+        assert_eq!(front_matter.format, FrontMatterFormat::Yaml);
+        let text = |value: &str| FrontMatterValue::Text(value.to_string());
+        let list = |items: &[&str]| {
+            FrontMatterValue::List(items.iter().map(|item| item.to_string()).collect())
+        };
+        assert_eq!(
+            front_matter.fields.get("title"),
+            Some(&text("Hello: world"))
+        );
+        assert_eq!(front_matter.fields.get("date"), Some(&text("2024-05-01")));
+        assert_eq!(
+            front_matter.fields.get("tags"),
+            Some(&list(&["news", "rust, lang"]))
+        );
+        assert_eq!(
+            front_matter.fields.get("authors"),
+            Some(&list(&["Ann", "Bob"]))
+        );
+
+        let document = parse_markdown(
+            "+++\ntitle = \"TOML\"\ntags = [\n  \"a\",\n  \"b\",\n]\n[author]\nname = 'Ann'\n+++\ntext",
+        )?;
+        let front_matter = document
+            .front_matter
+            .ok_or_else(|| anyhow!("Expected TOML front matter"))?;
+        assert_eq!(front_matter.format, FrontMatterFormat::Toml);
+        assert_eq!(front_matter.fields.get("title"), Some(&text("TOML")));
+        assert_eq!(front_matter.fields.get("tags"), Some(&list(&["a", "b"])));
+        assert_eq!(front_matter.fields.get("author.name"), Some(&text("Ann")));
+        assert_eq!(document.blocks.len(), 1);
+
+        let document = parse_markdown("text\n\n---\ntitle: x\n---\n")?;
+        assert_eq!(document.front_matter, None);
+
+        // A blank line usually separates the front matter from the body
+        let html = str_to_html("---\ntitle: Hi\n---\n\n# Body\n\nText")?;
+        assert_eq!(html, vec!["<h1 id=\"body\">Body</h1>", "<p>Text</p>"]);
+        let html = str_to_html("+++\ntitle = \"Hi\"\n+++\n\ntext")?;
+        assert_eq!(html, vec!["<p>text</p>"]);
 
         std::result::Result::Ok(())
     }
@@ -421,7 +656,19 @@ mod tests {
 
     #[test]
     fn check_horizontal_rule() -> Result<()> {
-        let inputs = vec!["---", "***", "___", "---   ", "***\n", "___\n"];
+        let inputs = vec![
+            "---",
+            "***",
+            "___",
+            "---   ",
+            "***\n",
+            "___\n",
+            "* * *",
+            "- - -",
+            "----",
+            "*****",
+            "  _ _ _\n",
+        ];
 
         for input in inputs {
             let mut pairs = parse_by_rule(Rule::thematic_break, input)?;
@@ -433,6 +680,21 @@ mod tests {
             assert_eq!(pair.as_str().trim(), input.trim());
         }
 
+        // Breaks take precedence over list items and end paragraphs
+        let html = str_to_html("- foo\n- - -\n* * *\nText\n* * *\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>foo</li>\n</ul>",
+                "<hr>",
+                "<hr>",
+                "<p>Text</p>",
+                "<hr>"
+            ]
+        );
+        let html = str_to_html("**\n\n*-*\n")?;
+        assert_eq!(html, vec!["<p>**</p>", "<p><em>-</em></p>"]);
+
         std::result::Result::Ok(())
     }
 
@@ -489,7 +751,7 @@ mod tests {
 
     #[test]
     fn check_document_unordered_list() -> Result<()> {
-        let input = "- item 1\n* item 2\n- item 3\n";
+        let input = "- item 1\n- item 2\n- item 3\n";
 
         let mut pairs = parse_by_rule(Rule::document_unordered_list, input)?;
         let pair = pairs
@@ -506,6 +768,16 @@ mod tests {
             assert_eq!(item.as_rule(), Rule::unordered_list_item);
         }
 
+        // Long lists parse in linear time without deep recursion
+        let document = parse_markdown(&"- a\n".repeat(5000))?;
+        match document.blocks.as_slice() {
+            [Block::List { items, tight, .. }] => {
+                assert_eq!(items.len(), 5000);
+                assert!(tight);
+            }
+            blocks => return Err(anyhow!("Expected a single list, found {blocks:?}")),
+        }
+
         std::result::Result::Ok(())
     }
 
@@ -593,4 +865,954 @@ mod tests {
 
         std::result::Result::Ok(())
     }
+
+    #[test]
+    fn check_document_ast() -> Result<()> {
+        let input =
+            "## Title\n\nSome **bold** and [a link](https://example.com)\n\n1. one\n2. two\n";
+        let document = parse_markdown(input)?;
+
+        assert_eq!(
+            document.blocks,
+            vec![
+                Block::Heading {
+                    level: 2,
+                    id: None,
+                    children: vec![Inline::Text("Title".to_string())],
+                },
+                Block::Paragraph {
+                    children: vec![
+                        Inline::Text("Some ".to_string()),
+                        Inline::Strong(vec![Inline::Text("bold".to_string())]),
+                        Inline::Text(" and ".to_string()),
+                        Inline::Link {
+                            url: "https://example.com".to_string(),
+                            title: None,
+                            children: vec![Inline::Text("a link".to_string())],
+                        },
+                    ],
+                },
+                Block::List {
+                    kind: ListKind::Ordered {
+                        start: 1,
+                        delimiter: '.',
+                    },
+                    tight: true,
+                    items: vec![
+                        ListItem {
+                            checked: None,
+                            children: vec![Block::Paragraph {
+                                children: vec![Inline::Text("one".to_string())],
+                            }],
+                        },
+                        ListItem {
+                            checked: None,
+                            children: vec![Block::Paragraph {
+                                children: vec![Inline::Text("two".to_string())],
+                            }],
+                        },
+                    ],
+                },
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_render_from_ast() -> Result<()> {
+        let document = parse_markdown("```rust\nlet x = a < b;\n```\n")?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::CodeBlock {
+                info: "rust".to_string(),
+                literal: "let x = a < b;".to_string(),
+            }]
+        );
+
+        let html = render_html(&document);
+        assert_eq!(
+            html,
+            vec!["<pre><code class=\"language-rust\">let x = a &lt; b;</code></pre>"]
+        );
+        assert_eq!(str_to_html("```rust\nlet x = a < b;\n```\n")?, html);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_nested_formatting() -> Result<()> {
+        let input = "**bold with *italic* and [a link](x)**";
+        let mut pairs = parse_by_rule(Rule::paragraph_text, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::paragraph_text, "nested bold")?;
+        let rules: Vec<Rule> = pair.into_inner().map(|p| p.as_rule()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                Rule::delimiter_run,
+                Rule::plain_text,
+                Rule::delimiter_run,
+                Rule::plain_text,
+                Rule::delimiter_run,
+                Rule::plain_text,
+                Rule::link,
+                Rule::delimiter_run
+            ]
+        );
+
+        let html = str_to_html("*a **b** c* and ~~x `code`~~ __u *i*__")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><em>a <strong>b</strong> c</em> and <del>x <code>code</code></del> <strong>u <em>i</em></strong></p>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_unmatched_markers_are_literal() -> Result<()> {
+        let html = str_to_html("2 * 3 is 6, [not a link and a ~ tilde")?;
+        assert_eq!(html, vec!["<p>2 * 3 is 6, [not a link and a ~ tilde</p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_heading_inline_content() -> Result<()> {
+        let input = "# The **API** for [foo](x)";
+        let mut pairs = parse_by_rule(Rule::h1_heading, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::h1_heading, "heading with markup")?;
+        let text = get_inner_pair(&pair, Rule::heading_text, "heading text")?;
+        let rules: Vec<Rule> = text.into_inner().map(|p| p.as_rule()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                Rule::plain_text,
+                Rule::delimiter_run,
+                Rule::plain_text,
+                Rule::delimiter_run,
+                Rule::plain_text,
+                Rule::link
+            ]
+        );
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<h1 id=\"the-api-for-foo\">The <strong>API</strong> for <a href=\"x\">foo</a></h1>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_list_item_inline_content() -> Result<()> {
+        let mut pairs = parse_by_rule(Rule::unordered_list_item, "- see `code`\n")?;
+        let pair = get_single_pair(&mut pairs, Rule::unordered_list_item, "list item")?;
+        let text = get_inner_pair(&pair, Rule::list_item_text, "list item text")?;
+        assert_eq!(text.as_str(), "see `code`");
+
+        let html = str_to_html("- see `code`\n- *it* and [x](y)\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>see <code>code</code></li>\n<li><em>it</em> and <a href=\"y\">x</a></li>\n</ul>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_nested_list() -> Result<()> {
+        let input = "- item\n  1. first\n  2. second\n     - deep\n- next\n";
+        let mut pairs = parse_by_rule(Rule::document_unordered_list, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_unordered_list, "nested list")?;
+        let items: Vec<_> = pair.into_inner().collect();
+        assert_eq!(items.len(), 2);
+        let text = get_inner_pair(&items[0], Rule::list_item_text, "item text")?;
+        assert_eq!(text.as_str(), "item");
+        let nested: Vec<Rule> = items[0].clone().into_inner().map(|p| p.as_rule()).collect();
+        assert_eq!(nested, vec![Rule::list_item_text, Rule::list_item_children]);
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>item\n<ol>\n<li>first</li>\n<li>second\n<ul>\n<li>deep</li>\n</ul>\n</li>\n</ol>\n</li>\n<li>next</li>\n</ul>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_nested_list_four_space_indent() -> Result<()> {
+        let html = str_to_html("1. one\n    - sub\n2. two\n")?;
+        assert_eq!(
+            html,
+            vec!["<ol>\n<li>one\n<ul>\n<li>sub</li>\n</ul>\n</li>\n<li>two</li>\n</ol>"]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_list_continuation_indent() -> Result<()> {
+        // Lines indented less than the item's content are siblings, not children
+        let html = str_to_html("- a\n - b\n")?;
+        assert_eq!(html, vec!["<ul>\n<li>a</li>\n<li>b</li>\n</ul>"]);
+        let html = str_to_html("1. a\n  2. b\n")?;
+        assert_eq!(html, vec!["<ol>\n<li>a</li>\n<li>b</li>\n</ol>"]);
+        let html = str_to_html("- a\n  b\n - c\n")?;
+        assert_eq!(html, vec!["<ul>\n<li>a\nb</li>\n<li>c</li>\n</ul>"]);
+        let html = str_to_html("- a\n  - b\n - c\n- d\n")?;
+        assert_eq!(
+            html,
+            vec!["<ul>\n<li>a\n<ul>\n<li>b</li>\n</ul>\n</li>\n<li>c</li>\n<li>d</li>\n</ul>"]
+        );
+        let html = str_to_html("1. item\n  - sub\n")?;
+        assert_eq!(
+            html,
+            vec!["<ol>\n<li>item</li>\n</ol>", "<ul>\n<li>sub</li>\n</ul>"]
+        );
+
+        // After a blank line, an under-indented line ends the list
+        let html = str_to_html("- a\n\n b\n")?;
+        assert_eq!(html, vec!["<ul>\n<li>a</li>\n</ul>", "<p>b</p>"]);
+
+        // A tab reaches the content column
+        let html = str_to_html("- a\n\t- b\n")?;
+        assert_eq!(
+            html,
+            vec!["<ul>\n<li>a\n<ul>\n<li>b</li>\n</ul>\n</li>\n</ul>"]
+        );
+
+        // Lazy lines stay paragraph text
+        let html = str_to_html("- a\n # b\n")?;
+        assert_eq!(html, vec!["<ul>\n<li>a\n# b</li>\n</ul>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_loose_list() -> Result<()> {
+        let input = "- a\n\n- b\n";
+        let mut pairs = parse_by_rule(Rule::document_unordered_list, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_unordered_list, "loose list")?;
+        assert_eq!(pair.as_str(), input);
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec!["<ul>\n<li>\n<p>a</p>\n</li>\n<li>\n<p>b</p>\n</li>\n</ul>"]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_list_item_block_content() -> Result<()> {
+        let input = "1. First line\nlazy line\n\n   Second paragraph\n\n   > quoted\n2. Next\n";
+        let document = parse_markdown(input)?;
+        let Some(Block::List { tight, items, .. }) = document.blocks.first() else {
+            return Err(anyhow!("Expected a list"));
+        };
+        assert!(!tight);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].children.len(), 3);
+        assert!(matches!(items[0].children[2], Block::BlockQuote { .. }));
+
+        let html = str_to_html("- item\n  ```rust\n  let a = 1;\n\n  let b = 2;\n  ```\n- next\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>item\n<pre><code class=\"language-rust\">let a = 1;\n\nlet b = 2;</code></pre>\n</li>\n<li>next</li>\n</ul>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_ordered_list_start_and_delimiter() -> Result<()> {
+        let input = "3) third\n4) fourth\n";
+        let mut pairs = parse_by_rule(Rule::document_ordered_list, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_ordered_list, "paren list")?;
+        assert_eq!(pair.as_str(), input);
+
+        let document = parse_markdown(input)?;
+        assert!(matches!(
+            document.blocks.as_slice(),
+            [Block::List {
+                kind: ListKind::Ordered {
+                    start: 3,
+                    delimiter: ')'
+                },
+                ..
+            }]
+        ));
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec!["<ol start=\"3\">\n<li>third</li>\n<li>fourth</li>\n</ol>"]
+        );
+
+        // Only lists starting at 1 interrupt a paragraph
+        let html = str_to_html("The year was\n1984. A good year\n")?;
+        assert_eq!(html, vec!["<p>The year was\n1984. A good year</p>"]);
+        let html = str_to_html("Steps:\n1) first\n")?;
+        assert_eq!(html, vec!["<p>Steps:</p>", "<ol>\n<li>first</li>\n</ol>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_marker_change_starts_new_list() -> Result<()> {
+        let input = "- a\n* b\n1. c\n2) d\n";
+        let mut pairs = parse_by_rule(Rule::document_unordered_list, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_unordered_list, "dash list")?;
+        assert_eq!(pair.as_str(), "- a\n");
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li>a</li>\n</ul>",
+                "<ul>\n<li>b</li>\n</ul>",
+                "<ol>\n<li>c</li>\n</ol>",
+                "<ol start=\"2\">\n<li>d</li>\n</ol>",
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_table() -> Result<()> {
+        let input = "| Name | Value |\n|:-----|:-----:|\n| a \\| b | `1` |\n";
+        let mut pairs = parse_by_rule(Rule::document_table, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::document_table, "table")?;
+        let rules: Vec<Rule> = pair.clone().into_inner().map(|p| p.as_rule()).collect();
+        assert_eq!(
+            rules,
+            vec![
+                Rule::table_header,
+                Rule::table_delimiter_row,
+                Rule::table_row
+            ]
+        );
+        let header = get_inner_pair(&pair, Rule::table_header, "table header")?;
+        let cells: Vec<&str> = header.into_inner().map(|p| p.as_str().trim()).collect();
+        assert_eq!(cells, vec!["Name", "Value"]);
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<table>\n<thead>\n<tr>\n<th align=\"left\">Name</th>\n<th align=\"center\">Value</th>\n</tr>\n</thead>\n<tbody>\n<tr>\n<td align=\"left\">a | b</td>\n<td align=\"center\"><code>1</code></td>\n</tr>\n</tbody>\n</table>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_table_rows_and_columns() -> Result<()> {
+        let document = parse_markdown("Intro\na | b\n--|--:\n1\n2 | 3 | 4\n\nAfter\n")?;
+        assert_eq!(document.blocks.len(), 3);
+        let Block::Table {
+            alignments, rows, ..
+        } = &document.blocks[1]
+        else {
+            return Err(anyhow!("Expected a table"));
+        };
+        assert_eq!(alignments, &vec![Alignment::None, Alignment::Right]);
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 2));
+        assert_eq!(rows[0][1], TableCell::default());
+
+        let html = str_to_html("| a | b |\n|---|\n")?;
+        assert!(html[0].starts_with("<p>"));
+
+        // Without matching column counts the lines stay one paragraph
+        let html = str_to_html("text\n| a |\n|---|---|\n| *x\ny* |")?;
+        assert_eq!(
+            html,
+            vec!["<p>text\n| a |\n|---|---|\n| <em>x\ny</em> |</p>"]
+        );
+
+        let html = str_to_html("| a | b |\n|---|---|\nplain line")?;
+        assert_eq!(html, str_to_html("| a | b |\n|---|---|\nplain line\n")?);
+        assert!(html[0].contains("<td>plain line</td>"));
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_task_list_items() -> Result<()> {
+        let mut pairs = parse_by_rule(Rule::unordered_list_item, "- [x] done\n")?;
+        let pair = get_single_pair(&mut pairs, Rule::unordered_list_item, "task item")?;
+        let marker = get_inner_pair(&pair, Rule::task_marker, "task marker")?;
+        assert_eq!(marker.as_str(), "[x] ");
+
+        let input = "- [ ] todo\n- [x] done\n  1. [ ] nested todo\n- plain\n";
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<ul>\n<li><input type=\"checkbox\" disabled> todo</li>\n<li><input type=\"checkbox\" disabled checked> done\n<ol>\n<li><input type=\"checkbox\" disabled> nested todo</li>\n</ol>\n</li>\n<li>plain</li>\n</ul>"
+            ]
+        );
+
+        let document = parse_markdown(input)?;
+        let tasks = document.task_items();
+        assert_eq!(tasks.len(), 3);
+        let open = tasks
+            .iter()
+            .filter(|item| item.checked == Some(false))
+            .count();
+        assert_eq!(open, 2);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_heading_ids() -> Result<()> {
+        let input = "## Installation {#install}";
+        let mut pairs = parse_by_rule(Rule::h2_heading, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::h2_heading, "heading with id")?;
+        let mut inner = pair.into_inner();
+        let text = get_single_pair(&mut inner, Rule::heading_text, "heading text")?;
+        assert_eq!(text.as_str(), "Installation ");
+        let id = get_single_pair(&mut inner, Rule::heading_id, "heading id")?;
+        assert_eq!(id.as_str(), "install");
+
+        let html = str_to_html(
+            "# Hello, World!\n## Hello World\n# Café **Über** `code`\n# Intro {#hello-world-2}\n# hello world\n# !!!",
+        )?;
+        assert_eq!(
+            html,
+            vec![
+                "<h1 id=\"hello-world\">Hello, World!</h1>",
+                "<h2 id=\"hello-world-1\">Hello World</h2>",
+                "<h1 id=\"café-über-code\">Café <strong>Über</strong> <code>code</code></h1>",
+                "<h1 id=\"hello-world-2\">Intro</h1>",
+                "<h1 id=\"hello-world-3\">hello world</h1>",
+                "<h1>!!!</h1>"
+            ]
+        );
+
+        let document = parse_markdown("Title {#custom}\n=====")?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Heading {
+                level: 1,
+                id: Some("custom".to_string()),
+                children: vec![Inline::Text("Title".to_string())]
+            }]
+        );
+        let html = str_to_html("Usage {#usage}\nmore\n---")?;
+        assert_eq!(
+            html,
+            vec!["<h2 id=\"usage-usage-more\">Usage {#usage}\nmore</h2>"]
+        );
+
+        let options = Options {
+            heading_anchors: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            str_to_html_with_options("# Title", &options)?,
+            vec![
+                "<h1 id=\"title\"><a class=\"anchor\" href=\"#title\" aria-hidden=\"true\"></a>Title</h1>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_setext_heading() -> Result<()> {
+        let input = "Main title\n==========\n";
+        let mut pairs = parse_by_rule(Rule::setext_heading, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::setext_heading, "setext heading")?;
+        let text = get_inner_pair(&pair, Rule::setext_text, "setext text")?;
+        assert_eq!(text.as_str(), "Main title\n");
+
+        let html = str_to_html("Main title\n==========\n\nSub **title**\n---\n\nText\n\n---\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<h1 id=\"main-title\">Main title</h1>",
+                "<h2 id=\"sub-title\">Sub <strong>title</strong></h2>",
+                "<p>Text</p>",
+                "<hr>"
+            ]
+        );
+
+        // Whitespace-only lines end the heading text like empty ones
+        let html = str_to_html("Foo\n   \n---\n")?;
+        assert_eq!(html, vec!["<p>Foo</p>", "<hr>"]);
+        let html = str_to_html("Foo\n   \nBar\n===\n")?;
+        assert_eq!(html, vec!["<p>Foo</p>", "<h1 id=\"bar\">Bar</h1>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_indented_code() -> Result<()> {
+        let input = "    fn main() {\n        x < 1;\n\n    }\n";
+        let mut pairs = parse_by_rule(Rule::indented_code, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::indented_code, "indented code")?;
+        assert_eq!(pair.as_str(), input);
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec!["<pre><code>fn main() {\n    x &lt; 1;\n\n}</code></pre>"]
+        );
+
+        // Lines holding only whitespace are blank, not code
+        let html = str_to_html("a\n\n    \nb")?;
+        assert_eq!(html, vec!["<p>a</p>", "<p>b</p>"]);
+        let html = str_to_html("text\n\n\t\nEND\n")?;
+        assert_eq!(html, vec!["<p>text</p>", "<p>END</p>"]);
+        let html = str_to_html("    code\n      \n")?;
+        assert_eq!(html, vec!["<pre><code>code</code></pre>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_indented_code_does_not_interrupt_paragraph() -> Result<()> {
+        let html = str_to_html("Paragraph\n    continued\n\n\tcode\n")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>Paragraph\ncontinued</p>",
+                "<pre><code>code</code></pre>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_code_fence_variants() -> Result<()> {
+        let input = "~~~python {linenos=true}\nprint(1)\n~~~~\n";
+        let mut pairs = parse_by_rule(Rule::code_fence, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::code_fence, "tilde fence")?;
+        let info = get_inner_pair(&pair, Rule::language_spec, "info string")?;
+        assert_eq!(info.as_str(), "python {linenos=true}");
+
+        let document = parse_markdown(input)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::CodeBlock {
+                info: "python {linenos=true}".to_string(),
+                literal: "print(1)".to_string(),
+            }]
+        );
+
+        let html = str_to_html("````markdown\n```c++\nint a;\n```\n````\n")?;
+        assert_eq!(
+            html,
+            vec!["<pre><code class=\"language-markdown\">```c++\nint a;\n```</code></pre>"]
+        );
+
+        // Only backtick fences reject backticks in the info string
+        let html = str_to_html("~~~ a`b\nx\n~~~\n")?;
+        assert_eq!(
+            html,
+            vec!["<pre><code class=\"language-a`b\">x</code></pre>"]
+        );
+        let html = str_to_html("``` a`b\nx\n```\n")?;
+        assert_eq!(html, vec!["<p><code>a`b x</code></p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_code_fence_indentation() -> Result<()> {
+        let html = str_to_html("  ```objective-c\n  two\n    four\n ```\n")?;
+        assert_eq!(
+            html,
+            vec!["<pre><code class=\"language-objective-c\">two\n  four</code></pre>"]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_link_reference_definition() -> Result<()> {
+        let input = "[Docs]: <https://example.com/a b> 'The \"docs\"'\n";
+        let mut pairs = parse_by_rule(Rule::link_reference_definition, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::link_reference_definition, "definition")?;
+        let label = get_inner_pair(&pair, Rule::link_label, "label")?;
+        assert_eq!(label.as_str(), "Docs");
+
+        let document = parse_markdown(input)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Definition(LinkDefinition {
+                label: "Docs".to_string(),
+                url: "https://example.com/a b".to_string(),
+                title: Some("The \"docs\"".to_string()),
+            })]
+        );
+        assert!(document.definitions().contains_key("docs"));
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_reference_links() -> Result<()> {
+        let input = "[Text][Ref], [ref][] and [REF] ![logo][ẞ]\n\n[ref]: /target \"Title\"\n[ss]: logo.png\n";
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"/target\" title=\"Title\">Text</a>, \
+                 <a href=\"/target\" title=\"Title\">ref</a> and \
+                 <a href=\"/target\" title=\"Title\">REF</a> \
+                 <img src=\"logo.png\" alt=\"logo\"></p>"
+            ]
+        );
+
+        let html = str_to_html("[missing] and [*text*][nope]")?;
+        assert_eq!(html, vec!["<p>[missing] and [<em>text</em>][nope]</p>"]);
+
+        let html = str_to_html("[a [b] c](u) and [x [y](z)](w)")?;
+        assert_eq!(
+            html,
+            vec!["<p><a href=\"u\">a [b] c</a> and [x <a href=\"z\">y</a>](w)</p>"]
+        );
+
+        // Unmatched brackets stay literal without exponential backtracking
+        let brackets = "[".repeat(50);
+        let html = str_to_html(&brackets)?;
+        assert_eq!(html, vec![format!("<p>{brackets}</p>")]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_link_titles() -> Result<()> {
+        let input = "[Docs](<docs/getting started.md> \"Getting started\")";
+        let mut pairs = parse_by_rule(Rule::link, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::link, "titled link")?;
+        let mut inner_iter = pair.into_inner();
+        get_single_pair(&mut inner_iter, Rule::link_content, "link content")?;
+        let url = get_single_pair(&mut inner_iter, Rule::link_url, "link url")?;
+        assert_eq!(url.as_str(), "<docs/getting started.md>");
+        let title = get_single_pair(&mut inner_iter, Rule::link_title, "link title")?;
+        assert_eq!(title.as_str(), "\"Getting started\"");
+
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec!["<p><a href=\"docs/getting started.md\" title=\"Getting started\">Docs</a></p>"]
+        );
+
+        let html = str_to_html("![Logo](logo.png 'The \"logo\"') [wiki](/Foo_(bar) (Foo))")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><img src=\"logo.png\" alt=\"Logo\" title=\"The &quot;logo&quot;\"> \
+                 <a href=\"/Foo_(bar)\" title=\"Foo\">wiki</a></p>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_autolink() -> Result<()> {
+        let input = "<https://example.com/a?b=1>";
+        let mut pairs = parse_by_rule(Rule::autolink, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::autolink, "autolink")?;
+        let uri = get_inner_pair(&pair, Rule::autolink_uri, "autolink uri")?;
+        assert_eq!(uri.as_str(), "https://example.com/a?b=1");
+
+        let html = str_to_html("<https://example.com> <user@example.com> <not a link>")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"https://example.com\">https://example.com</a> \
+                 <a href=\"mailto:user@example.com\">user@example.com</a> &lt;not a link&gt;</p>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_extended_autolinks() -> Result<()> {
+        let input =
+            "See www.example.com/help. (https://example.com/a(b)) or mail dev+x@example.org.";
+        assert_eq!(
+            str_to_html(input)?,
+            vec![
+                "<p>See www.example.com/help. (https://example.com/a(b)) or mail dev+x@example.org.</p>"
+            ]
+        );
+
+        let options = Options {
+            extended_autolinks: true,
+            ..Options::default()
+        };
+        let html = str_to_html_with_options(input, &options)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>See <a href=\"http://www.example.com/help\">www.example.com/help</a>. \
+                 (<a href=\"https://example.com/a(b)\">https://example.com/a(b)</a>) \
+                 or mail <a href=\"mailto:dev+x@example.org\">dev+x@example.org</a>.</p>"
+            ]
+        );
+
+        let html = str_to_html_with_options(
+            "[www.example.com](x) `www.example.com` xwww.example.com",
+            &options,
+        )?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"x\">www.example.com</a> <code>www.example.com</code> xwww.example.com</p>"
+            ]
+        );
+
+        // A bare `www.` has no domain to link
+        let html = str_to_html_with_options("see www. here, www.. or www.a", &options)?;
+        assert_eq!(
+            html,
+            vec!["<p>see www. here, www.. or <a href=\"http://www.a\">www.a</a></p>"]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_footnote_definition() -> Result<()> {
+        let input = "[^note]: First paragraph.\n\n    Second paragraph.\n";
+        let mut pairs = parse_by_rule(Rule::footnote_definition, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::footnote_definition, "footnote")?;
+        let label = get_inner_pair(&pair, Rule::footnote_label, "footnote label")?;
+        assert_eq!(label.as_str(), "note");
+
+        let document = parse_markdown(input)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::FootnoteDefinition(FootnoteDefinition {
+                label: "note".to_string(),
+                children: vec![
+                    Block::Paragraph {
+                        children: vec![Inline::Text("First paragraph.".to_string())]
+                    },
+                    Block::Paragraph {
+                        children: vec![Inline::Text("Second paragraph.".to_string())]
+                    },
+                ],
+            })]
+        );
+
+        let document = parse_markdown("[^x]:\n\n    para\n")?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::FootnoteDefinition(FootnoteDefinition {
+                label: "x".to_string(),
+                children: vec![Block::Paragraph {
+                    children: vec![Inline::Text("para".to_string())]
+                }],
+            })]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_footnotes() -> Result<()> {
+        let input =
+            "A[^b] B[^a] C[^B] D[^none]\n\n[^a]: Note *a*.\n[^b]: Note b.\n[^unused]: Unused.\n";
+        let html = str_to_html(input)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>A<sup class=\"footnote-ref\"><a href=\"#fn-b\" id=\"fnref-b\" data-footnote-ref>1</a></sup> \
+                 B<sup class=\"footnote-ref\"><a href=\"#fn-a\" id=\"fnref-a\" data-footnote-ref>2</a></sup> \
+                 C<sup class=\"footnote-ref\"><a href=\"#fn-b\" id=\"fnref-b-2\" data-footnote-ref>1</a></sup> \
+                 D[^none]</p>",
+                "<section class=\"footnotes\" data-footnotes>\n<ol>\n\
+                 <li id=\"fn-b\">\n<p>Note b. \
+                 <a href=\"#fnref-b\" class=\"footnote-backref\" aria-label=\"Back to reference 1\">↩</a> \
+                 <a href=\"#fnref-b-2\" class=\"footnote-backref\" aria-label=\"Back to reference 1-2\">↩<sup class=\"footnote-ref\">2</sup></a></p>\n</li>\n\
+                 <li id=\"fn-a\">\n<p>Note <em>a</em>. \
+                 <a href=\"#fnref-a\" class=\"footnote-backref\" aria-label=\"Back to reference 2\">↩</a></p>\n</li>\n\
+                 </ol>\n</section>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_html_block() -> Result<()> {
+        let input = "<details>\n<summary>More</summary>\n\n*text*\n\n</details>\n";
+        let mut pairs = parse_by_rule(Rule::html_block, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::html_block, "html block")?;
+        assert_eq!(pair.as_str(), "<details>\n<summary>More</summary>");
+
+        let options = Options {
+            unsafe_html: true,
+            ..Options::default()
+        };
+        let html = str_to_html_with_options(input, &options)?;
+        assert_eq!(
+            html,
+            vec![
+                "<details>\n<summary>More</summary>",
+                "<p><em>text</em></p>",
+                "</details>"
+            ]
+        );
+
+        let html = str_to_html_with_options("<!-- a\n\nb -->\nText\n<custom>\n", &options)?;
+        assert_eq!(html, vec!["<!-- a\n\nb -->", "<p>Text\n<custom></p>"]);
+
+        let html = str_to_html("<div>\n*x*\n</div>")?;
+        assert_eq!(html, vec!["<p>&lt;div&gt;\n*x*\n&lt;/div&gt;</p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_inline_html() -> Result<()> {
+        let input = "Press <kbd>Ctrl</kbd><br/> <!-- note --> <a href='x' title=\"y\">z</a> 1 < 2";
+        let options = Options {
+            unsafe_html: true,
+            ..Options::default()
+        };
+        let html = str_to_html_with_options(input, &options)?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>Press <kbd>Ctrl</kbd><br/> <!-- note --> <a href='x' title=\"y\">z</a> 1 &lt; 2</p>"
+            ]
+        );
+
+        let html = str_to_html("Press <kbd>Ctrl</kbd>")?;
+        assert_eq!(html, vec!["<p>Press &lt;kbd&gt;Ctrl&lt;/kbd&gt;</p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_line_breaks() -> Result<()> {
+        let input = "one\ntwo  \nthree\\\nfour\\";
+        let document = parse_markdown(input)?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Paragraph {
+                children: vec![
+                    Inline::Text("one".to_string()),
+                    Inline::SoftBreak,
+                    Inline::Text("two".to_string()),
+                    Inline::HardBreak,
+                    Inline::Text("three".to_string()),
+                    Inline::HardBreak,
+                    Inline::Text("four\\".to_string()),
+                ]
+            }]
+        );
+
+        let html = str_to_html(input)?;
+        assert_eq!(html, vec!["<p>one\ntwo<br>\nthree<br>\nfour\\</p>"]);
+
+        let options = Options {
+            hard_breaks: true,
+            ..Options::default()
+        };
+        let html = str_to_html_with_options("one\ntwo", &options)?;
+        assert_eq!(html, vec!["<p>one<br>\ntwo</p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_multi_backtick_code_span() -> Result<()> {
+        let input = "``code with ` inside``";
+        let mut pairs = parse_by_rule(Rule::inline_code, input)?;
+        let pair = get_single_pair(&mut pairs, Rule::inline_code, "code span")?;
+        let content = get_inner_pair(&pair, Rule::code_span_content, "code span content")?;
+        assert_eq!(content.as_str(), "code with ` inside");
+
+        let html = str_to_html("``code with ` inside`` ` `` ` `  ` ``foo`")?;
+        assert_eq!(
+            html,
+            vec!["<p><code>code with ` inside</code> <code>``</code> <code>  </code> ``foo`</p>"]
+        );
+
+        let html = str_to_html("``\nfoo\nbar  \nbaz\n``")?;
+        assert_eq!(html, vec!["<p><code>foo bar   baz</code></p>"]);
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_entity_reference() -> Result<()> {
+        for input in ["&copy;", "&#169;", "&#xA9;", "&ClockwiseContourIntegral;"] {
+            let mut pairs = parse_by_rule(Rule::entity_reference, input)?;
+            let pair = get_single_pair(&mut pairs, Rule::entity_reference, "entity")?;
+            assert_eq!(pair.as_str(), input);
+        }
+
+        let document = parse_markdown("&copy; &#169; &#xA9; &#0; &bogus; AT&T")?;
+        assert_eq!(
+            document.blocks,
+            vec![Block::Paragraph {
+                children: vec![Inline::Text("© © © \u{FFFD} &bogus; AT&T".to_string())]
+            }]
+        );
+
+        let html =
+            str_to_html("&lt;b&gt; &amp;amp; `&amp;` [x](/f&ouml;&ouml; \"&quot;t&quot;\")")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p>&lt;b&gt; &amp;amp; <code>&amp;amp;</code> \
+                 <a href=\"/föö\" title=\"&quot;t&quot;\">x</a></p>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
+
+    #[test]
+    fn check_backslash_escapes() -> Result<()> {
+        let pairs = parse_by_rule(Rule::escape_sequence, "\\a");
+        assert!(pairs.is_err());
+
+        let html = str_to_html("\\*not emphasis\\* C:\\temp\\new \\\\ `\\*` \\")?;
+        assert_eq!(
+            html,
+            vec!["<p>*not emphasis* C:\\temp\\new \\ <code>\\*</code> \\</p>"]
+        );
+
+        let html = str_to_html("[a\\b](/p\\ath\\) \"t\\\"\")\n\n\\# not a heading")?;
+        assert_eq!(
+            html,
+            vec![
+                "<p><a href=\"/p\\ath)\" title=\"t&quot;\">a\\b</a></p>",
+                "<p># not a heading</p>"
+            ]
+        );
+
+        std::result::Result::Ok(())
+    }
 }